@@ -21,8 +21,13 @@ pub fn validate_input_database(path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Validates that the output path is writable
-pub fn validate_output_path(path: &Path) -> Result<()> {
+/// Validates that the output path is writable, optionally creating the parent
+/// directory when it is missing.
+///
+/// When `create_dirs` is true a missing parent directory is created with
+/// `create_dir_all` rather than being reported as an error, so first-run setup
+/// on a fresh host works without a manual `mkdir`.
+pub fn validate_output_path(path: &Path, create_dirs: bool) -> Result<()> {
     // If path exists, check it's a file (not directory)
     if path.exists() && !path.is_file() {
         return Err(MigrationError::OutputNotAFile(path.to_path_buf()));
@@ -36,8 +41,20 @@ pub fn validate_output_path(path: &Path) -> Result<()> {
             return Ok(());
         }
 
-        // For non-empty parents, check if they exist
-        if !parent.exists() {
+        if parent.is_dir() {
+            return Ok(());
+        }
+
+        // Distinguish "exists but is not a directory" (e.g. a regular file) from
+        // "does not exist" so the caller gets a clear error in the former case.
+        if parent.exists() {
+            return Err(MigrationError::ParentNotADirectory(parent.to_path_buf()));
+        }
+
+        if create_dirs {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| MigrationError::CreateDir(parent.to_path_buf(), e.to_string()))?;
+        } else {
             return Err(MigrationError::ParentDirNotFound(parent.to_path_buf()));
         }
     }
@@ -54,28 +71,28 @@ mod tests {
     fn test_validate_output_path_relative_current_dir() {
         // Should succeed - parent is current directory
         let path = PathBuf::from("new.sqlite");
-        assert!(validate_output_path(&path).is_ok());
+        assert!(validate_output_path(&path, false).is_ok());
     }
 
     #[test]
     fn test_validate_output_path_explicit_current_dir() {
         // Should succeed - parent is explicit "."
         let path = PathBuf::from("./new.sqlite");
-        assert!(validate_output_path(&path).is_ok());
+        assert!(validate_output_path(&path, false).is_ok());
     }
 
     #[test]
-    fn test_validate_output_path_nonexistent_subdir() {
-        // Should fail - subdirectory doesn't exist
+    fn test_validate_output_path_nonexistent_subdir_no_create() {
+        // Should fail - subdirectory doesn't exist and creation is disabled
         let path = PathBuf::from("definitely_nonexistent_directory_12345/new.sqlite");
-        assert!(validate_output_path(&path).is_err());
+        assert!(validate_output_path(&path, false).is_err());
     }
 
     #[test]
-    fn test_validate_output_path_absolute_nonexistent() {
-        // Should fail - absolute path with nonexistent parent
+    fn test_validate_output_path_absolute_nonexistent_no_create() {
+        // Should fail - absolute path with nonexistent parent, creation disabled
         let path = PathBuf::from("/definitely_nonexistent_path_xyz_12345/new.sqlite");
-        let result = validate_output_path(&path);
+        let result = validate_output_path(&path, false);
         assert!(result.is_err());
         if let Err(MigrationError::ParentDirNotFound(_)) = result {
             // Expected error type
@@ -83,4 +100,17 @@ mod tests {
             panic!("Expected ParentDirNotFound error");
         }
     }
+
+    #[test]
+    fn test_validate_output_path_creates_missing_parent() {
+        // Should succeed and create the parent directory when create_dirs is set
+        let dir = std::env::temp_dir().join("lilium_webdav_validation_test_12345");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("notes.sqlite");
+
+        assert!(validate_output_path(&path, true).is_ok());
+        assert!(dir.is_dir());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -14,32 +14,130 @@ pub struct Cli {
 pub enum Commands {
     /// Start the WebDAV server
     Serve(ServeArgs),
+
+    /// Apply pending schema migrations to the database
+    Migrate(MigrateArgs),
+
+    /// Print the resolved config-file and default database locations
+    ConfigLocation(ConfigLocationArgs),
+
+    /// Hash a password with Argon2id and print the PHC string
+    HashPassword(HashPasswordArgs),
+
+    /// Mint a signed bearer token for the given user_id
+    IssueToken(IssueTokenArgs),
+}
+
+/// Arguments for the migrate command
+#[derive(Parser)]
+pub struct MigrateArgs {
+    /// Path to the SQLite database (created if it does not exist)
+    #[arg(short, long)]
+    pub database: PathBuf,
+
+    /// Print the pending migration versions without applying them
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
-/// Arguments for the serve command
+/// Arguments for the config-location command
+#[derive(Parser)]
+pub struct ConfigLocationArgs {
+    /// Path to a TOML config file (default: XDG config dir)
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+}
+
+/// Arguments for the hash-password command.
+#[derive(Parser)]
+pub struct HashPasswordArgs {
+    /// Password to hash (prompted on stdin if omitted)
+    #[arg(short, long)]
+    pub password: Option<String>,
+}
+
+/// Arguments for the issue-token command.
+#[derive(Parser)]
+pub struct IssueTokenArgs {
+    /// user_id to embed as the token's `sub` claim
+    #[arg(long)]
+    pub user_id: String,
+
+    /// HS256 signing secret (default: LILIUM_WEBDAV_JWT_SECRET or the config file)
+    #[arg(long)]
+    pub secret: Option<String>,
+
+    /// Token lifetime in seconds
+    #[arg(long, default_value_t = 3600)]
+    pub ttl_secs: u64,
+
+    /// Path to a TOML config file (default: XDG config dir)
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+}
+
+/// Arguments for the serve command.
+///
+/// Most values may come from the config file instead; the flags here, when
+/// given, take precedence over the corresponding config-file entries.
+/// `--config`, `--password-file`, `--no-permission-checks`, `--auto-migrate`
+/// and `--create-dirs` have no config-file counterpart.
 #[derive(Parser)]
 pub struct ServeArgs {
     /// Path to the SQLite database
     #[arg(short, long)]
-    pub database: PathBuf,
+    pub database: Option<PathBuf>,
 
-    /// Host address to bind to
-    #[arg(short = 'H', long, default_value = "127.0.0.1")]
-    pub host: String,
+    /// Host address to bind to (default: 127.0.0.1)
+    #[arg(short = 'H', long)]
+    pub host: Option<String>,
 
-    /// Port to listen on
-    #[arg(short, long, default_value = "4918")]
-    pub port: u16,
+    /// Port to listen on (default: 4918)
+    #[arg(short, long)]
+    pub port: Option<u16>,
 
     /// Login username for Basic Auth
     #[arg(short, long)]
-    pub username: String,
+    pub username: Option<String>,
 
-    /// Password for Basic Auth
+    /// Argon2id PHC hash for Basic Auth (see the hash-password subcommand)
     #[arg(short = 'P', long)]
-    pub password: String,
+    pub password: Option<String>,
+
+    /// Read the Argon2id PHC hash from a file (single trailing newline trimmed)
+    #[arg(long)]
+    pub password_file: Option<PathBuf>,
 
     /// User ID in the database (defaults to username if not specified)
     #[arg(long)]
     pub user_id: Option<String>,
+
+    /// Path to a TOML config file (default: XDG config dir)
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+
+    /// Skip the filesystem permission checks on the database and its parents
+    #[arg(long)]
+    pub no_permission_checks: bool,
+
+    /// Apply any pending schema migrations before starting the server
+    #[arg(long)]
+    pub auto_migrate: bool,
+
+    /// Create the database's parent directory if it is missing
+    #[arg(long, action = ArgAction::Set, default_value_t = true)]
+    pub create_dirs: bool,
+
+    /// PEM certificate chain for HTTPS (requires --tls-key); omit for plain HTTP
+    #[arg(long)]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key for HTTPS (requires --tls-cert)
+    #[arg(long)]
+    pub tls_key: Option<PathBuf>,
+
+    /// HS256 secret for verifying bearer tokens (see the issue-token subcommand);
+    /// omit to accept Basic Auth only
+    #[arg(long)]
+    pub jwt_secret: Option<String>,
 }
@@ -1,9 +1,9 @@
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
-use dav_server::memls::MemLs;
 use dav_server::DavHandler;
 use http::{Request, Response, StatusCode};
 use http_body_util::Full;
@@ -11,84 +11,301 @@ use hyper::body::{Bytes, Incoming};
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper_util::rt::TokioIo;
-use subtle::ConstantTimeEq;
 use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
 
-use crate::cli::{Commands, ServeArgs};
-use crate::webdav::{extract_basic_auth, SqliteFs};
+use crate::cli::{
+    Commands, ConfigLocationArgs, HashPasswordArgs, IssueTokenArgs, MigrateArgs, ServeArgs,
+};
+use crate::webdav::{
+    build_pool, extract_basic_auth, extract_bearer, AuthProvider, DbPool, SingleUserProvider,
+    SqliteFs, SqliteLs, TableAuthProvider,
+};
 
 pub fn execute(command: Commands) -> Result<()> {
     match command {
         Commands::Serve(args) => serve(args),
+        Commands::Migrate(args) => migrate(args),
+        Commands::ConfigLocation(args) => config_location(args),
+        Commands::HashPassword(args) => hash_password(args),
+        Commands::IssueToken(args) => issue_token(args),
     }
 }
 
+/// Hash a password with Argon2id and print the resulting PHC string, so it
+/// can be copied into the config file, `--password`, or the `users` table.
+fn hash_password(args: HashPasswordArgs) -> Result<()> {
+    let password = match args.password {
+        Some(p) => p,
+        None => {
+            eprint!("Password: ");
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            line.trim_end_matches(['\n', '\r']).to_string()
+        }
+    };
+
+    println!("{}", crate::password::hash(&password)?);
+    Ok(())
+}
+
+/// Environment variable carrying the JWT signing secret.
+const JWT_SECRET_ENV: &str = "LILIUM_WEBDAV_JWT_SECRET";
+
+/// Resolve the JWT secret from the CLI flag, environment variable, or config
+/// file, in that order of precedence. Unlike the Basic Auth password, bearer
+/// auth is entirely optional, so this returns `None` rather than erroring
+/// when no source supplies one.
+fn resolve_jwt_secret(flag: Option<String>, config_secret: Option<String>) -> Option<String> {
+    flag.or_else(|| std::env::var(JWT_SECRET_ENV).ok())
+        .or(config_secret)
+}
+
+/// Mint a signed bearer token for `args.user_id`, so a companion app can
+/// authenticate without sending a reusable password on every request.
+fn issue_token(args: IssueTokenArgs) -> Result<()> {
+    let file = crate::config::load(args.config.as_deref())?;
+    let secret = resolve_jwt_secret(args.secret, file.jwt_secret).ok_or_else(|| {
+        anyhow::anyhow!(
+            "no JWT secret given (pass --secret, set {}, or add jwt_secret to the config file)",
+            JWT_SECRET_ENV
+        )
+    })?;
+
+    let token = crate::jwt::issue(&args.user_id, &secret, Duration::from_secs(args.ttl_secs))?;
+    println!("{}", token);
+    Ok(())
+}
+
+fn config_location(args: ConfigLocationArgs) -> Result<()> {
+    let config_path = crate::config::resolved_config_path(args.config.as_deref())
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "<unknown: $HOME and $XDG_CONFIG_HOME unset>".to_string());
+    let db_path = crate::config::default_database_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "<unknown: $HOME and $XDG_DATA_HOME unset>".to_string());
+
+    println!("Config file:      {}", config_path);
+    println!("Default database: {}", db_path);
+    Ok(())
+}
+
+fn migrate(args: MigrateArgs) -> Result<()> {
+    let pending = crate::migrate::run(&args.database, args.dry_run)?;
+
+    if pending.is_empty() {
+        println!("Database is up to date; no migrations pending.");
+    } else if args.dry_run {
+        println!("Pending migrations: {:?}", pending);
+    } else {
+        println!("Applied migrations: {:?}", pending);
+    }
+
+    Ok(())
+}
+
 struct ServerConfig {
     db_path: std::path::PathBuf,
+    pool: DbPool,
     username: String,
-    password: String,
+    password_hash: String,
     user_id: String,
+    jwt_secret: Option<String>,
 }
 
-/// Compare credentials in constant time to prevent timing attacks
-fn verify_credentials(
-    provided: &crate::webdav::auth::Credentials,
-    expected_user: &str,
-    expected_pass: &str,
-) -> bool {
-    let username_match = provided.username.as_bytes().ct_eq(expected_user.as_bytes());
-    let password_match = provided.password.as_bytes().ct_eq(expected_pass.as_bytes());
-
-    // Both must match - use constant-time AND
-    (username_match & password_match).into()
+/// Environment variable carrying the Basic Auth password hash.
+const PASSWORD_ENV: &str = "LILIUM_WEBDAV_PASSWORD";
+
+/// Resolve the Basic Auth password from exactly one of the command-line
+/// sources, falling back to the config file when none is given.
+///
+/// Precedence is `--password` > `--password-file` > `LILIUM_WEBDAV_PASSWORD` >
+/// config file, but supplying more than one of the three explicit sources is an
+/// error rather than a silent choice.
+fn resolve_password(
+    flag: Option<String>,
+    file_path: Option<std::path::PathBuf>,
+    config_password: Option<String>,
+    no_permission_checks: bool,
+) -> Result<String> {
+    let env_password = std::env::var(PASSWORD_ENV).ok();
+
+    let sources = flag.is_some() as u8
+        + file_path.is_some() as u8
+        + env_password.is_some() as u8;
+    if sources > 1 {
+        anyhow::bail!(
+            "password supplied by more than one source; use only one of --password, --password-file, or {}",
+            PASSWORD_ENV
+        );
+    }
+
+    if let Some(p) = flag {
+        Ok(p)
+    } else if let Some(path) = file_path {
+        read_password_file(&path, no_permission_checks)
+    } else if let Some(p) = env_password {
+        Ok(p)
+    } else if let Some(p) = config_password {
+        Ok(p)
+    } else {
+        anyhow::bail!("no password given (pass --password, --password-file, or set it in the config file)")
+    }
+}
+
+/// Read a password from a file, trimming a single trailing newline and
+/// refusing to read a file that other local users can read.
+fn read_password_file(path: &std::path::Path, no_permission_checks: bool) -> Result<String> {
+    crate::permissions::check_secret_file(path, no_permission_checks)?;
+
+    let mut password = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("reading password file {:?}: {}", path, e))?;
+    if password.ends_with('\n') {
+        password.pop();
+        if password.ends_with('\r') {
+            password.pop();
+        }
+    }
+    Ok(password)
 }
 
 fn serve(args: ServeArgs) -> Result<()> {
+    // Merge the config file (if any) with the CLI flags; flags win.
+    let file = crate::config::load(args.config.as_deref())?;
+
+    let db_path = args
+        .database
+        .or(file.database)
+        .ok_or_else(|| anyhow::anyhow!("no database path given (pass --database or set it in the config file)"))?;
+    let host = args.host.or(file.host).unwrap_or_else(|| "127.0.0.1".to_string());
+    let port = args.port.or(file.port).unwrap_or(4918);
+    let username = args
+        .username
+        .or(file.username)
+        .ok_or_else(|| anyhow::anyhow!("no username given (pass --username or set it in the config file)"))?;
+    let password_hash = resolve_password(
+        args.password,
+        args.password_file,
+        file.password,
+        args.no_permission_checks,
+    )?;
+    let user_id = args
+        .user_id
+        .or(file.user_id)
+        .unwrap_or_else(|| username.clone());
+    let tls_cert = args.tls_cert.or(file.tls_cert);
+    let tls_key = args.tls_key.or(file.tls_key);
+    let jwt_secret = resolve_jwt_secret(args.jwt_secret, file.jwt_secret);
+
+    // Ensure the database's parent directory exists (creating it when asked)
+    // so first-run setup works without a manual mkdir.
+    crate::db::validate_output_path(&db_path, args.create_dirs)?;
+
+    // Optionally bring the schema up to date (and create the DB) before serving.
+    if args.auto_migrate {
+        let db_existed = db_path.exists();
+        let applied = crate::migrate::run(&db_path, false)?;
+        if !applied.is_empty() {
+            println!("Applied migrations: {:?}", applied);
+        }
+        // `migrate::run` opens the path with a bare `Connection::open`, so a
+        // brand-new file inherits the process umask (typically 0644) rather
+        // than the 0600 the permission check below requires. Tighten it now
+        // so first-run setup doesn't immediately refuse to serve.
+        if !db_existed {
+            crate::permissions::secure_new_database(&db_path)?;
+        }
+    }
+
     // Validate database exists
-    if !args.database.exists() {
-        anyhow::bail!("Database file not found: {:?}", args.database);
+    if !db_path.exists() {
+        anyhow::bail!("Database file not found: {:?}", db_path);
     }
 
-    let addr: SocketAddr = format!("{}:{}", args.host, args.port).parse()?;
-    let user_id = args.user_id.unwrap_or_else(|| args.username.clone());
+    // Refuse to run when the database (or a directory leading to it) is exposed
+    // to other local users, since it holds the Basic-Auth password too.
+    crate::permissions::check_database_path(&db_path, args.no_permission_checks)?;
+
+    // Refuse to serve against a database that predates a migration this build
+    // expects (e.g. --auto-migrate wasn't passed against a pre-existing DB):
+    // otherwise every request that touches the missing schema fails with a
+    // bare 500 instead of a clear "run migrate" error.
+    crate::migrate::check_up_to_date(&db_path)?;
+
+    // Build the shared connection pool once, up front.
+    let pool = build_pool(&db_path)
+        .map_err(|e| anyhow::anyhow!("failed to open database pool: {}", e))?;
+
+    // Build the TLS acceptor once, up front, so a bad cert/key fails fast
+    // instead of on the first connection.
+    let tls = match (tls_cert, tls_key) {
+        (Some(cert), Some(key)) => Some(crate::tls::build_acceptor(&cert, &key)?),
+        (None, None) => None,
+        _ => anyhow::bail!("--tls-cert and --tls-key must be given together"),
+    };
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
+    let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
     let config = Arc::new(ServerConfig {
-        db_path: args.database,
-        username: args.username,
-        password: args.password,
+        db_path,
+        pool,
+        username,
+        password_hash,
         user_id,
+        jwt_secret,
     });
 
-    println!("Starting WebDAV server at http://{}", addr);
+    println!("Starting WebDAV server at {}://{}", scheme, addr);
     println!("Database: {:?}", config.db_path);
     println!("Login: {} -> user_id: {}", config.username, config.user_id);
 
     // Run the async server
-    tokio::runtime::Runtime::new()?.block_on(async { run_server(addr, config).await })?;
+    tokio::runtime::Runtime::new()?.block_on(async { run_server(addr, config, tls).await })?;
 
     Ok(())
 }
 
-async fn run_server(addr: SocketAddr, config: Arc<ServerConfig>) -> Result<()> {
+async fn run_server(addr: SocketAddr, config: Arc<ServerConfig>, tls: Option<TlsAcceptor>) -> Result<()> {
     let listener = TcpListener::bind(addr).await?;
 
+    // Only now, with the listener actually accepting connections, tell
+    // systemd (if we're a `Type=notify` unit) that dependent units can start.
+    crate::sdnotify::notify_ready(&format!("Serving WebDAV at {}", addr));
+    crate::sdnotify::spawn_watchdog();
+
     loop {
         let (stream, _) = listener.accept().await?;
-        let io = TokioIo::new(stream);
         let config = config.clone();
+        let tls = tls.clone();
 
         tokio::spawn(async move {
-            let service = service_fn(move |req| {
-                let config = config.clone();
-                async move { handle_request(req, config).await }
-            });
-
-            if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
-                eprintln!("Connection error: {}", e);
+            match tls {
+                Some(acceptor) => match acceptor.accept(stream).await {
+                    Ok(tls_stream) => serve_connection(TokioIo::new(tls_stream), config).await,
+                    Err(e) => eprintln!("TLS handshake error: {}", e),
+                },
+                None => serve_connection(TokioIo::new(stream), config).await,
             }
         });
     }
 }
 
+/// Serve a single already-accepted (and, if applicable, already-TLS-wrapped)
+/// connection until the client disconnects.
+async fn serve_connection<I>(io: I, config: Arc<ServerConfig>)
+where
+    I: hyper::rt::Read + hyper::rt::Write + Unpin + 'static,
+{
+    let service = service_fn(move |req| {
+        let config = config.clone();
+        async move { handle_request(req, config).await }
+    });
+
+    if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
+        eprintln!("Connection error: {}", e);
+    }
+}
+
 async fn handle_request(
     req: Request<Incoming>,
     config: Arc<ServerConfig>,
@@ -102,51 +319,84 @@ async fn handle_request(
         }
     }
 
-    // Extract credentials from Basic Auth
-    let creds = match extract_basic_auth(req.headers()) {
-        Ok(c) => c,
-        Err(_) => {
-            eprintln!("[AUTH] No valid auth header found");
-            // Don't leak error details to client
-            let response = Response::builder()
-                .status(StatusCode::UNAUTHORIZED)
-                .header("WWW-Authenticate", "Basic realm=\"WebDAV\"")
-                .body(Full::new(Bytes::from("Unauthorized")))
-                .unwrap();
-            return Ok(response);
-        }
-    };
+    // Accept either Basic Auth (checked against the `users` table/CLI user)
+    // or a bearer token (checked against the configured JWT secret), so a
+    // companion app can use a token while WebDAV clients keep using Basic Auth.
+    let user_id = if let Ok(creds) = extract_basic_auth(req.headers()) {
+        eprintln!("[AUTH] Extracted credentials - username: '{}'", creds.username);
 
-    eprintln!("[AUTH] Extracted credentials - username: '{}', password: '{}' (len: {})",
-        creds.username, creds.password, creds.password.len());
-    eprintln!("[AUTH] Expected credentials - username: '{}', password: '{}' (len: {})",
-        config.username, config.password, config.password.len());
-
-    // Validate username and password using constant-time comparison
-    if !verify_credentials(&creds, &config.username, &config.password) {
-        eprintln!("[AUTH] Credential verification failed");
+        // Look the user up in the `users` table, falling back to the single
+        // CLI/config user when the table has no matching row (or is empty).
+        let provider = TableAuthProvider::new(
+            config.pool.clone(),
+            SingleUserProvider {
+                username: config.username.clone(),
+                password_hash: config.password_hash.clone(),
+                user_id: config.user_id.clone(),
+            },
+        );
+        match provider.authenticate(&creds.username, &creds.password) {
+            Some(user) => user.user_id,
+            None => {
+                eprintln!("[AUTH] Credential verification failed");
+                let response = Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .header("WWW-Authenticate", "Basic realm=\"WebDAV\"")
+                    .body(Full::new(Bytes::from("Invalid credentials")))
+                    .unwrap();
+                return Ok(response);
+            }
+        }
+    } else if let (Some(secret), Ok(token)) =
+        (config.jwt_secret.as_deref(), extract_bearer(req.headers()))
+    {
+        match crate::jwt::verify(&token, secret) {
+            Some(user_id) => {
+                eprintln!("[AUTH] Bearer token verified - user_id: '{}'", user_id);
+                user_id
+            }
+            None => {
+                eprintln!("[AUTH] Bearer token verification failed");
+                let response = Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .header("WWW-Authenticate", "Basic realm=\"WebDAV\"")
+                    .body(Full::new(Bytes::from("Invalid credentials")))
+                    .unwrap();
+                return Ok(response);
+            }
+        }
+    } else {
+        eprintln!("[AUTH] No valid auth header found");
+        // Don't leak error details to client
         let response = Response::builder()
             .status(StatusCode::UNAUTHORIZED)
             .header("WWW-Authenticate", "Basic realm=\"WebDAV\"")
-            .body(Full::new(Bytes::from("Invalid credentials")))
+            .body(Full::new(Bytes::from("Unauthorized")))
             .unwrap();
         return Ok(response);
-    }
+    };
 
     eprintln!("[AUTH] Credential verification succeeded");
 
-    // Create filesystem using the configured user_id
-    let fs = SqliteFs::new(config.db_path.clone(), config.user_id.clone());
+    // Create filesystem using the matched user's user_id, sharing the pool
+    let fs = SqliteFs::new(config.pool.clone(), user_id);
+    // Kept alongside the one handed to dav-server so a GET can look up the
+    // note's real stored MIME type below, since dav-server only ever infers
+    // Content-Type from the path's extension.
+    let fs_for_mime = fs.clone();
 
-    // Create DAV handler with autoindex and locking support
+    // Create DAV handler with autoindex and SQLite-backed locking support
     let dav = DavHandler::builder()
         .filesystem(Box::new(fs))
-        .locksystem(MemLs::new())
+        .locksystem(SqliteLs::new(config.pool.clone()))
         .autoindex(true)
         .build_handler();
 
     // Convert request body for dav-server
     let (parts, body) = req.into_parts();
+    let is_get = parts.method == http::Method::GET;
+    let request_path = parts.uri.path().to_string();
+    let client_accepts_gzip = crate::compression::accepts_gzip(&parts.headers);
     let body_bytes = match http_body_util::BodyExt::collect(body).await {
         Ok(collected) => collected.to_bytes(),
         Err(_) => {
@@ -158,6 +408,23 @@ async fn handle_request(
         }
     };
 
+    // Transparently decompress a gzip-encoded request body (e.g. a PUT from
+    // a compressing client) before handing it to dav-server.
+    let body_bytes = if crate::compression::is_gzip_encoded(&parts.headers) {
+        match crate::compression::decompress(&body_bytes) {
+            Ok(decompressed) => Bytes::from(decompressed),
+            Err(_) => {
+                let response = Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(Full::new(Bytes::from("Invalid gzip body")))
+                    .unwrap();
+                return Ok(response);
+            }
+        }
+    } else {
+        body_bytes
+    };
+
     // Check for forbidden operations on root
     if parts.method == http::Method::DELETE {
         let path = parts.uri.path().trim_matches('/');
@@ -183,6 +450,18 @@ async fn handle_request(
         Err(_) => Bytes::new(),
     };
 
+    // A binary note's own stored MIME type (set via PUT, see
+    // `create_or_update_blob`) is the source of truth for GET, overriding
+    // dav-server's extension-based guess in case the two disagree (e.g. the
+    // note's `syntax` isn't a recognized extension).
+    if is_get && parts.status.is_success() {
+        if let Ok(Some(mime_type)) = fs_for_mime.mime_type_for_path(&request_path) {
+            if let Ok(value) = mime_type.parse() {
+                parts.headers.insert("content-type", value);
+            }
+        }
+    }
+
     // Fix Content-Type to include charset=utf-8 for text/* types
     if let Some(content_type) = parts.headers.get("content-type") {
         if let Ok(ct_str) = content_type.to_str() {
@@ -193,6 +472,20 @@ async fn handle_request(
         }
     }
 
+    // Gzip the response body when the client accepts it and it's large
+    // enough to be worth the overhead (autoindex HTML listings and large
+    // text notes benefit most).
+    let body_bytes = match crate::compression::compress_if_worthwhile(&body_bytes, client_accepts_gzip) {
+        Some(compressed) => {
+            parts.headers.insert("content-encoding", "gzip".parse().unwrap());
+            parts
+                .headers
+                .insert("content-length", compressed.len().to_string().parse().unwrap());
+            Bytes::from(compressed)
+        }
+        None => body_bytes,
+    };
+
     // Log response
     eprintln!("[HTTP] Response: {} (body: {} bytes)", parts.status, body_bytes.len());
     eprintln!("[HEADERS] Response headers:");
@@ -0,0 +1,54 @@
+//! Gzip compression/decompression for WebDAV request and response bodies.
+//!
+//! The autoindex HTML listings and large text notes this server returns
+//! compress well, so gzip-encode a response when the client sent
+//! `Accept-Encoding: gzip`, and gzip-decode an incoming body tagged
+//! `Content-Encoding: gzip` (e.g. a PUT from a compressing client) before
+//! handing it to dav-server. Tiny bodies are left uncompressed since gzip's
+//! own header/footer overhead isn't worth it below [`MIN_COMPRESS_LEN`].
+
+use std::io::Read;
+
+use flate2::read::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use http::HeaderMap;
+
+/// Bodies smaller than this are sent uncompressed even when the client
+/// accepts gzip.
+const MIN_COMPRESS_LEN: usize = 256;
+
+/// Whether the client's `Accept-Encoding` header lists `gzip`.
+pub fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get(http::header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|enc| enc.trim().eq_ignore_ascii_case("gzip")))
+}
+
+/// Whether the request body is gzip-encoded, per its `Content-Encoding` header.
+pub fn is_gzip_encoded(headers: &HeaderMap) -> bool {
+    headers
+        .get(http::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("gzip"))
+}
+
+/// Gzip-compress `bytes`, unless `client_accepts_gzip` is false or `bytes` is
+/// too small for compression to be worthwhile.
+pub fn compress_if_worthwhile(bytes: &[u8], client_accepts_gzip: bool) -> Option<Vec<u8>> {
+    if !client_accepts_gzip || bytes.len() < MIN_COMPRESS_LEN {
+        return None;
+    }
+    let mut encoder = GzEncoder::new(bytes, Compression::default());
+    let mut compressed = Vec::new();
+    encoder.read_to_end(&mut compressed).ok()?;
+    Some(compressed)
+}
+
+/// Gzip-decompress `bytes`.
+pub fn decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(bytes);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
@@ -0,0 +1,80 @@
+//! Layered configuration: values come from a TOML config file and are then
+//! overridden by any CLI flags the user passes.
+//!
+//! The default config file lives under the XDG config directory
+//! (`$XDG_CONFIG_HOME/lilium_webdav/config.toml`, falling back to
+//! `~/.config/lilium_webdav/config.toml`); `--config <path>` points elsewhere.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Application name used as the directory component under XDG base dirs.
+const APP: &str = "lilium_webdav";
+
+/// Configuration read from the TOML config file. Every field is optional so
+/// that the file can specify as much or as little as the user likes; anything
+/// absent is supplied by a CLI flag or a built-in default.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<PathBuf>,
+    pub user_id: Option<String>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    pub jwt_secret: Option<String>,
+}
+
+/// The directory holding the config file, resolved via XDG.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(x) = std::env::var_os("XDG_CONFIG_HOME").filter(|s| !s.is_empty()) {
+        return Some(PathBuf::from(x).join(APP));
+    }
+    std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config").join(APP))
+}
+
+/// The default config-file path (`.../lilium_webdav/config.toml`).
+pub fn default_config_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("config.toml"))
+}
+
+/// The directory holding application data, resolved via XDG.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(x) = std::env::var_os("XDG_DATA_HOME").filter(|s| !s.is_empty()) {
+        return Some(PathBuf::from(x).join(APP));
+    }
+    std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("share").join(APP))
+}
+
+/// The default database path (`.../lilium_webdav/notes.sqlite`).
+pub fn default_database_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("notes.sqlite"))
+}
+
+/// Resolve the config path that will actually be consulted: the explicit
+/// `--config` path if given, otherwise the XDG default.
+pub fn resolved_config_path(explicit: Option<&Path>) -> Option<PathBuf> {
+    explicit.map(Path::to_path_buf).or_else(default_config_path)
+}
+
+/// Load the config file, returning an empty [`Config`] when no file exists at
+/// the default location. An explicitly requested `--config` path that is
+/// missing is an error.
+pub fn load(explicit: Option<&Path>) -> Result<Config> {
+    match resolved_config_path(explicit) {
+        Some(path) if path.exists() => {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading config file {}", path.display()))?;
+            toml::from_str(&text)
+                .with_context(|| format!("parsing config file {}", path.display()))
+        }
+        _ if explicit.is_some() => {
+            bail!("config file not found: {}", explicit.unwrap().display())
+        }
+        _ => Ok(Config::default()),
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+/// Result type used throughout the database, validation, and migration layers.
+pub type Result<T> = std::result::Result<T, MigrationError>;
+
+/// Errors that can occur while validating, opening, or migrating the notes
+/// database.
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The input database path does not exist.
+    InputNotFound(PathBuf),
+    /// The input database path exists but is not a regular file.
+    InputNotAFile(PathBuf),
+    /// The file could not be opened as a valid SQLite database.
+    InvalidDatabase(PathBuf, String),
+    /// The output path exists but is not a regular file.
+    OutputNotAFile(PathBuf),
+    /// The parent directory of the output path does not exist.
+    ParentDirNotFound(PathBuf),
+    /// The parent of the output path exists but is not a directory.
+    ParentNotADirectory(PathBuf),
+    /// The parent directory could not be created.
+    CreateDir(PathBuf, String),
+    /// One or more path components are group- or world-accessible in a way
+    /// that would expose the database (and its embedded credentials) to other
+    /// local users. Each entry is the offending path and its raw `st_mode`.
+    InsecurePermissions(Vec<(PathBuf, u32)>),
+    /// A file's permission bits could not be changed.
+    SetPermissions(PathBuf, String),
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::InputNotFound(path) => {
+                write!(f, "input database not found: {}", path.display())
+            }
+            MigrationError::InputNotAFile(path) => {
+                write!(f, "input database path is not a file: {}", path.display())
+            }
+            MigrationError::InvalidDatabase(path, msg) => {
+                write!(f, "not a valid SQLite database ({}): {}", path.display(), msg)
+            }
+            MigrationError::OutputNotAFile(path) => {
+                write!(f, "output path is not a file: {}", path.display())
+            }
+            MigrationError::ParentDirNotFound(path) => {
+                write!(f, "parent directory not found: {}", path.display())
+            }
+            MigrationError::ParentNotADirectory(path) => {
+                write!(f, "parent path is not a directory: {}", path.display())
+            }
+            MigrationError::CreateDir(path, msg) => {
+                write!(f, "could not create directory {}: {}", path.display(), msg)
+            }
+            MigrationError::InsecurePermissions(violations) => {
+                writeln!(
+                    f,
+                    "refusing to run: {} path(s) are accessible to other local users",
+                    violations.len()
+                )?;
+                for (path, mode) in violations {
+                    writeln!(f, "  {} (mode {:04o})", path.display(), mode & 0o7777)?;
+                }
+                write!(
+                    f,
+                    "set {}=true to override",
+                    crate::permissions::DISABLE_ENV
+                )
+            }
+            MigrationError::SetPermissions(path, msg) => {
+                write!(f, "could not set permissions on {}: {}", path.display(), msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<rusqlite::Error> for MigrationError {
+    fn from(e: rusqlite::Error) -> Self {
+        MigrationError::InvalidDatabase(PathBuf::new(), e.to_string())
+    }
+}
@@ -0,0 +1,50 @@
+//! HS256 bearer-token issuance and verification.
+//!
+//! Lets clients (e.g. a companion app) authenticate without sending a
+//! reusable password on every request: mint a token once with [`issue`] (the
+//! `issue-token` CLI subcommand), then send it as `Authorization: Bearer
+//! <token>`. The token's `sub` claim becomes the `user_id` used to build
+//! `SqliteFs`, same as a matched `users` table row. Basic Auth keeps working
+//! for clients that can't send bearer tokens.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: u64,
+}
+
+/// Mint a signed HS256 token embedding `user_id` as `sub`, expiring after `ttl`.
+pub fn issue(user_id: &str, secret: &str, ttl: Duration) -> Result<String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .context("system clock before UNIX epoch")?;
+    let claims = Claims {
+        sub: user_id.to_string(),
+        exp: (now + ttl).as_secs(),
+    };
+
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .context("signing token")
+}
+
+/// Verify a token's signature and expiry against `secret`, returning its
+/// `sub` claim (the user_id) on success.
+pub fn verify(token: &str, secret: &str) -> Option<String> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .ok()
+    .map(|data| data.claims.sub)
+}
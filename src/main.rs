@@ -1,5 +1,16 @@
 mod cli;
 mod commands;
+mod compression;
+mod config;
+#[path = "../db/mod.rs"]
+mod db;
+mod error;
+mod jwt;
+mod migrate;
+mod password;
+mod permissions;
+mod sdnotify;
+mod tls;
 mod webdav;
 
 use clap::Parser;
@@ -0,0 +1,226 @@
+//! Embedded, versioned schema migrations.
+//!
+//! The server can initialize and upgrade the notes schema on its own, without
+//! an external migration tool. Each migration is baked into the binary as an
+//! ordered `{ version, name, up_sql }` triple; on run we record applied
+//! versions in a `schema_migrations` table and execute every pending migration
+//! in ascending order, one transaction per migration so a mid-sequence failure
+//! leaves a consistent applied prefix.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// A single forward-only schema migration embedded in the binary.
+struct Migration {
+    version: i64,
+    name: &'static str,
+    up_sql: &'static str,
+}
+
+/// The ordered list of migrations known to this build.
+///
+/// Append new entries with a strictly increasing `version`; never edit,
+/// reorder, or remove a migration once it has shipped.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        up_sql: "
+        CREATE TABLE IF NOT EXISTS folders (
+            id         TEXT PRIMARY KEY,
+            title      TEXT NOT NULL,
+            parent_id  TEXT REFERENCES folders(id) ON DELETE CASCADE,
+            user_id    TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS notes (
+            id         TEXT PRIMARY KEY,
+            title      TEXT NOT NULL,
+            content    TEXT NOT NULL DEFAULT '',
+            syntax     TEXT NOT NULL DEFAULT 'md',
+            parent_id  TEXT REFERENCES folders(id) ON DELETE CASCADE,
+            user_id    TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_folders_parent ON folders(parent_id, user_id);
+        CREATE INDEX IF NOT EXISTS idx_notes_parent   ON notes(parent_id, user_id);
+    ",
+    },
+    Migration {
+        version: 2,
+        name: "dead_properties",
+        up_sql: "
+        CREATE TABLE IF NOT EXISTS props (
+            entity_id TEXT NOT NULL,
+            namespace TEXT NOT NULL DEFAULT '',
+            name      TEXT NOT NULL,
+            value     BLOB,
+            PRIMARY KEY (entity_id, namespace, name)
+        );
+    ",
+    },
+    Migration {
+        version: 3,
+        name: "note_relationships",
+        up_sql: "
+        CREATE TABLE IF NOT EXISTS note_relationships (
+            src_note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            dst_note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            kind        TEXT NOT NULL,
+            PRIMARY KEY (src_note_id, dst_note_id, kind)
+        );
+        CREATE INDEX IF NOT EXISTS idx_rel_src ON note_relationships(src_note_id, kind);
+        CREATE INDEX IF NOT EXISTS idx_rel_dst ON note_relationships(dst_note_id, kind);
+    ",
+    },
+    Migration {
+        version: 4,
+        name: "webdav_locks",
+        up_sql: "
+        CREATE TABLE IF NOT EXISTS locks (
+            token        TEXT PRIMARY KEY,
+            path         TEXT NOT NULL,
+            principal    TEXT,
+            owner        TEXT,
+            shared       INTEGER NOT NULL,
+            deep         INTEGER NOT NULL,
+            timeout_secs INTEGER,
+            expires_at   INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_locks_path ON locks(path);
+    ",
+    },
+    Migration {
+        version: 5,
+        name: "users",
+        up_sql: "
+        CREATE TABLE IF NOT EXISTS users (
+            username TEXT PRIMARY KEY,
+            password TEXT NOT NULL, -- Argon2id PHC string, not plaintext
+            user_id  TEXT NOT NULL
+        );
+    ",
+    },
+    Migration {
+        version: 6,
+        name: "binary_notes",
+        up_sql: "
+        ALTER TABLE notes ADD COLUMN is_binary INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE notes ADD COLUMN content_blob BLOB;
+        ALTER TABLE notes ADD COLUMN mime_type TEXT;
+    ",
+    },
+];
+
+/// The newest migration version embedded in this build.
+fn newest_version() -> i64 {
+    MIGRATIONS.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// Apply every pending migration to the database at `db_path`, creating the
+/// file if it does not yet exist. Returns the versions that were applied (or,
+/// with `dry_run`, that would be applied) in ascending order.
+pub fn run(db_path: &Path, dry_run: bool) -> Result<Vec<i64>> {
+    let mut conn = Connection::open(db_path)
+        .with_context(|| format!("opening database {}", db_path.display()))?;
+    conn.execute("PRAGMA foreign_keys = ON", [])?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (version INTEGER PRIMARY KEY, name TEXT, applied_at TEXT)",
+        [],
+    )?;
+
+    let applied = applied_versions(&conn)?;
+
+    // Refuse to downgrade: a database written by a newer binary may use schema
+    // this build does not understand.
+    if let Some(&max_applied) = applied.iter().max() {
+        let newest = newest_version();
+        if max_applied > newest {
+            bail!(
+                "database schema version {} is newer than the newest embedded migration {}; upgrade the binary",
+                max_applied,
+                newest
+            );
+        }
+    }
+
+    let mut pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .collect();
+    pending.sort_by_key(|m| m.version);
+
+    let versions: Vec<i64> = pending.iter().map(|m| m.version).collect();
+    if dry_run {
+        return Ok(versions);
+    }
+
+    for m in pending {
+        let tx = conn.transaction()?;
+        tx.execute_batch(m.up_sql)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?, ?, datetime('now'))",
+            params![m.version, m.name],
+        )?;
+        tx.commit()?;
+    }
+
+    Ok(versions)
+}
+
+/// Confirm that the database at `db_path` has every migration this build
+/// knows about already applied.
+///
+/// Serving against a database that predates a migration fails confusingly:
+/// every query that touches a column or table the pending migration would
+/// have added (e.g. `notes.is_binary` from `binary_notes`) errors with
+/// SQLite's bare "no such column"/"no such table", surfaced to WebDAV clients
+/// as a bare 500. Call this before serving so a stale database instead gets a
+/// clear, actionable error up front.
+pub fn check_up_to_date(db_path: &Path) -> Result<()> {
+    let conn = Connection::open(db_path)
+        .with_context(|| format!("opening database {}", db_path.display()))?;
+
+    // A database that has never been migrated at all (no `schema_migrations`
+    // table) is just as stale as one missing the newest migration.
+    let applied = if table_exists(&conn, "schema_migrations")? {
+        applied_versions(&conn)?
+    } else {
+        Default::default()
+    };
+
+    let newest = newest_version();
+    if applied.contains(&newest) {
+        return Ok(());
+    }
+
+    bail!(
+        "database at {} is missing schema migrations (newest applied: {:?}, newest known: {}); run `webdav_server migrate --database {}` first, or pass --auto-migrate",
+        db_path.display(),
+        applied.iter().max(),
+        newest,
+        db_path.display(),
+    );
+}
+
+fn table_exists(conn: &Connection, name: &str) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?",
+            params![name],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Read the set of already-applied migration versions, ascending.
+fn applied_versions(conn: &Connection) -> Result<std::collections::BTreeSet<i64>> {
+    let mut stmt = conn.prepare("SELECT version FROM schema_migrations")?;
+    let rows = stmt.query_map([], |row| row.get::<_, i64>(0))?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
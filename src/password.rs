@@ -0,0 +1,34 @@
+//! Argon2id password hashing and verification.
+//!
+//! Stored passwords (in the config file, on the CLI, and in the `users`
+//! table) are Argon2id PHC strings
+//! (`$argon2id$v=19$m=...,t=...,p=...$salt$hash`) rather than plaintext, so a
+//! leaked config file or database doesn't hand out credentials directly. Use
+//! the `hash-password` CLI subcommand to produce one.
+
+use anyhow::Result;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+/// Hash `password` with Argon2id (fresh random salt), returning a PHC string.
+pub fn hash(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| anyhow::anyhow!("hashing password: {}", e))
+}
+
+/// Verify `password` against a stored Argon2id PHC string, recovering the
+/// salt and parameters from the string itself. Argon2's own verification is
+/// already constant-time. Returns `false` (rather than erroring) for a
+/// malformed PHC string, same as a simple mismatch.
+pub fn verify(password: &str, phc: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(phc) else {
+        return false;
+    };
+    Argon2::default()
+        .verify_password(password.as_bytes(), &parsed)
+        .is_ok()
+}
@@ -0,0 +1,133 @@
+//! Filesystem permission hardening for sensitive files.
+//!
+//! The server keeps notes plus a Basic-Auth password inside a SQLite database,
+//! so it should refuse to run when that file — or any directory on the way to
+//! it — is readable or writable by other local users. This module is modeled
+//! loosely on the `fs-mistrust` crate: before opening a secret, we walk the
+//! path and collect every component whose mode bits are too permissive,
+//! reporting all of them together rather than failing on the first.
+//!
+//! The checks are a no-op on non-Unix targets, and can be disabled wholesale
+//! via the [`DISABLE_ENV`] environment variable (useful for containers running
+//! as root with a permissive umask).
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{MigrationError, Result};
+
+/// Environment variable that, when set to a truthy value (`1`/`true`/`yes`),
+/// disables all filesystem permission checks.
+pub const DISABLE_ENV: &str = "LILIUM_FS_DISABLE_PERMISSION_CHECKS";
+
+fn disabled_via_env() -> bool {
+    std::env::var(DISABLE_ENV)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Verify that the database file and each of its ancestor directories are not
+/// group- or world-accessible in a way that would leak the notes and
+/// credentials it holds.
+///
+/// The file itself is rejected if readable or writable by group/other
+/// (`mode & 0o077`); ancestor directories are rejected only if group/other
+/// *writable* (`mode & 0o022`), since a world-writable parent lets another user
+/// swap the file out from under us. The walk stops at the user's home directory
+/// or the filesystem root.
+///
+/// In WAL mode SQLite also writes note content into `<db>-wal` and `<db>-shm`
+/// sidecar files alongside the main file, so those are held to the same
+/// `mode & 0o077` standard as the database itself.
+pub fn check_database_path(db_path: &Path, disabled: bool) -> Result<()> {
+    if disabled || disabled_via_env() {
+        return Ok(());
+    }
+    let wal = sidecar_path(db_path, "-wal");
+    let shm = sidecar_path(db_path, "-shm");
+    check_path(db_path, 0o077, &[wal, shm])
+}
+
+/// Verify that a secret file supplied out-of-band (e.g. a `--password-file`) is
+/// not readable by group or other.
+pub fn check_secret_file(path: &Path, disabled: bool) -> Result<()> {
+    if disabled || disabled_via_env() {
+        return Ok(());
+    }
+    check_path(path, 0o077, &[])
+}
+
+/// Build the path of a WAL-mode sidecar file (`<db>-wal` / `<db>-shm`) next to
+/// the database.
+fn sidecar_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(unix)]
+fn check_path(path: &Path, file_mask: u32, sidecar_files: &[PathBuf]) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let mut violations: Vec<(PathBuf, u32)> = Vec::new();
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+
+    // The secret file itself, and any sidecar files that carry the same
+    // content, must not be accessible to group/other. A sidecar that doesn't
+    // exist (e.g. WAL mode was never used) is simply skipped.
+    for candidate in std::iter::once(path).chain(sidecar_files.iter().map(|p| p.as_path())) {
+        if let Ok(meta) = std::fs::metadata(candidate) {
+            let mode = meta.mode();
+            if mode & file_mask != 0 {
+                violations.push((candidate.to_path_buf(), mode));
+            }
+        }
+    }
+
+    // Each ancestor directory, up to (but not including) $HOME or the root,
+    // must not be group- or world-writable.
+    for ancestor in path.ancestors().skip(1) {
+        if ancestor.as_os_str().is_empty() {
+            continue;
+        }
+        if home.as_deref() == Some(ancestor) || ancestor == Path::new("/") {
+            break;
+        }
+        if let Ok(meta) = std::fs::metadata(ancestor) {
+            let mode = meta.mode();
+            if mode & 0o022 != 0 {
+                violations.push((ancestor.to_path_buf(), mode));
+            }
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(MigrationError::InsecurePermissions(violations))
+    }
+}
+
+#[cfg(not(unix))]
+fn check_path(_path: &Path, _file_mask: u32, _sidecar_files: &[PathBuf]) -> Result<()> {
+    Ok(())
+}
+
+/// Lock a just-created database file down to `0600`.
+///
+/// SQLite creates a new file with the process umask applied (typically
+/// `0644`), which is exactly what [`check_database_path`] rejects — so
+/// `serve --auto-migrate` against a path that doesn't exist yet must tighten
+/// the permissions itself right after creating it, or the first-run flow
+/// would refuse to start against its own freshly created database.
+#[cfg(unix)]
+pub fn secure_new_database(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| MigrationError::SetPermissions(path.to_path_buf(), e.to_string()))
+}
+
+#[cfg(not(unix))]
+pub fn secure_new_database(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Minimal systemd `sd_notify` integration for `Type=notify` units.
+//!
+//! systemd points a unit at a readiness socket via the `$NOTIFY_SOCKET`
+//! environment variable; writing `READY=1` to it tells systemd (and anything
+//! that depends on this unit via `After=`/`Requires=`) that the server is
+//! actually ready to answer requests, not just that the process has started.
+//! `$WATCHDOG_USEC`, if set, asks the unit to keep proving liveness by
+//! sending `WATCHDOG=1` at least that often.
+//!
+//! This talks to the notify socket directly over a Unix datagram rather than
+//! pulling in the `sd-notify` crate, so it degrades to a no-op off Linux and
+//! when `$NOTIFY_SOCKET` isn't set (i.e. not running under systemd).
+
+use std::time::Duration;
+
+/// Send `READY=1` plus a human-readable `STATUS=` line to `$NOTIFY_SOCKET`,
+/// if set. Called once the listener is bound and about to start accepting.
+pub fn notify_ready(status: &str) {
+    send(&format!("READY=1\nSTATUS={}\n", status));
+}
+
+/// If `$WATCHDOG_USEC` is set, spawn a task that sends `WATCHDOG=1` at half
+/// the requested interval for as long as the process runs.
+pub fn spawn_watchdog() {
+    let Some(interval) = watchdog_interval() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            send("WATCHDOG=1\n");
+        }
+    });
+}
+
+/// Parse `$WATCHDOG_USEC` into half its interval, the cadence systemd
+/// recommends pinging at so a single missed tick doesn't trip the watchdog.
+fn watchdog_interval() -> Option<Duration> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    Some(Duration::from_micros(usec) / 2)
+}
+
+#[cfg(unix)]
+fn send(message: &str) {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let socket = match UnixDatagram::unbound() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("[SDNOTIFY] Failed to create notify socket: {}", e);
+            return;
+        }
+    };
+
+    // A leading '@' denotes a Linux abstract-namespace socket, spelled as a
+    // NUL byte rather than '@' at the kernel level; everything else is a
+    // regular filesystem path.
+    let bytes = path.as_os_str().as_bytes();
+    let result = if let Some(name) = bytes.strip_prefix(b"@") {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::linux::net::SocketAddrExt;
+            std::os::unix::net::SocketAddr::from_abstract_name(name)
+                .and_then(|addr| socket.send_to_addr(message.as_bytes(), &addr))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+        }
+    } else {
+        socket.send_to(message.as_bytes(), &path)
+    };
+
+    if let Err(e) = result {
+        eprintln!("[SDNOTIFY] Failed to notify {:?}: {}", path, e);
+    }
+}
+
+#[cfg(not(unix))]
+fn send(_message: &str) {}
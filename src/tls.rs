@@ -0,0 +1,43 @@
+//! TLS termination via rustls, for exposing the WebDAV endpoint directly over
+//! HTTPS without a reverse proxy in front of it.
+//!
+//! Only built when `--tls-cert`/`--tls-key` are both given; absent, the
+//! server falls back to the existing cleartext HTTP behavior.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use rustls_pemfile::{certs, private_key};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+/// Build a `TlsAcceptor` from a PEM certificate chain and a PEM private key.
+pub fn build_acceptor(cert_path: &Path, key_path: &Path) -> Result<TlsAcceptor> {
+    let cert_chain = load_certs(cert_path)?;
+    let key = load_key(key_path)?;
+
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)
+        .context("building TLS server config")?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).with_context(|| format!("opening TLS cert {:?}", path))?;
+    certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("parsing TLS cert {:?}", path))
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).with_context(|| format!("opening TLS key {:?}", path))?;
+    private_key(&mut BufReader::new(file))
+        .with_context(|| format!("parsing TLS key {:?}", path))?
+        .ok_or_else(|| anyhow::anyhow!("no private key found in {:?}", path))
+}
@@ -1,4 +1,7 @@
-use headers::{authorization::Basic, Authorization, HeaderMapExt};
+use headers::{
+    authorization::{Basic, Bearer},
+    Authorization, HeaderMapExt,
+};
 use http::HeaderMap;
 
 /// Credentials extracted from Basic Auth header
@@ -29,6 +32,16 @@ pub fn extract_basic_auth(headers: &HeaderMap) -> Result<Credentials, AuthError>
         })
 }
 
+/// Extract a raw bearer token from `Authorization: Bearer <token>`.
+/// Returns error if no valid auth header is present. The token itself is
+/// validated separately (see [`crate::jwt`]).
+pub fn extract_bearer(headers: &HeaderMap) -> Result<String, AuthError> {
+    headers
+        .typed_get::<Authorization<Bearer>>()
+        .ok_or(AuthError::Missing)
+        .map(|auth| auth.token().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,4 +99,27 @@ mod tests {
         // This should fail to parse
         assert!(extract_basic_auth(&headers).is_err());
     }
+
+    #[test]
+    fn test_valid_bearer_auth() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer abc.def.ghi".parse().unwrap());
+
+        let token = extract_bearer(&headers).unwrap();
+        assert_eq!(token, "abc.def.ghi");
+    }
+
+    #[test]
+    fn test_missing_bearer_header() {
+        let headers = HeaderMap::new();
+        assert!(matches!(extract_bearer(&headers), Err(AuthError::Missing)));
+    }
+
+    #[test]
+    fn test_basic_header_is_not_bearer() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Basic dXNlcjpwYXNz".parse().unwrap());
+
+        assert!(extract_bearer(&headers).is_err());
+    }
 }
@@ -5,7 +5,7 @@ use std::time::SystemTime;
 use bytes::{Buf, Bytes};
 use dav_server::fs::{DavFile, DavMetaData, FsError, FsFuture, FsResult};
 
-use super::filesystem::{NoteData, SqliteFs};
+use super::filesystem::{guess_mime_type, parse_datetime, NoteData, SqliteFs};
 
 /// A WebDAV file backed by a note from SQLite
 pub struct SqliteDavFile {
@@ -27,7 +27,7 @@ impl Debug for SqliteDavFile {
 
 impl SqliteDavFile {
     pub fn new(note: NoteData, fs: SqliteFs) -> Self {
-        let content = note.content.clone().into_bytes();
+        let content = note.bytes();
         Self {
             note,
             cursor: Cursor::new(content),
@@ -38,7 +38,7 @@ impl SqliteDavFile {
     }
 
     pub fn new_writable(note: NoteData, fs: SqliteFs, parent_id: Option<String>) -> Self {
-        let content = note.content.clone().into_bytes();
+        let content = note.bytes();
         Self {
             note,
             cursor: Cursor::new(content),
@@ -77,7 +77,7 @@ impl DavMetaData for NoteMetaData {
 
 impl DavFile for SqliteDavFile {
     fn metadata(&mut self) -> FsFuture<'_, Box<dyn DavMetaData>> {
-        let len = self.note.content.len() as u64;
+        let len = self.note.len();
         let modified = parse_datetime(&self.note.updated_at);
         let created = parse_datetime(&self.note.created_at);
 
@@ -137,51 +137,101 @@ impl DavFile for SqliteDavFile {
             return Box::pin(async { Ok(()) });
         }
 
-        // Get the content from the cursor
-        let content = match std::str::from_utf8(self.cursor.get_ref()) {
-            Ok(s) => s.to_string(),
-            Err(_) => return Box::pin(async { Err(FsError::GeneralFailure) }),
+        let Some(ref fs) = self.fs else {
+            return Box::pin(async { Err(FsError::GeneralFailure) });
         };
-
-        // Update the note in the database
-        if let Some(ref fs) = self.fs {
-            let fs = fs.clone();
-            let parent_id = self.parent_id.clone();
-            let title = self.note.title.clone();
-            let syntax = self.note.syntax.clone();
-
-            Box::pin(async move {
-                fs.create_or_update_note(
-                    parent_id.as_deref(),
-                    &title,
-                    &syntax,
-                    &content,
-                ).map(|_| ())
-            })
-        } else {
-            Box::pin(async { Err(FsError::GeneralFailure) })
+        let fs = fs.clone();
+        let parent_id = self.parent_id.clone();
+        let title = self.note.title.clone();
+        let syntax = self.note.syntax.clone();
+
+        // Store as text when the cursor holds valid UTF-8 (the common case:
+        // markdown/plaintext notes); otherwise fall back to the blob path so
+        // binary uploads (images, PDFs, ...) round-trip intact instead of
+        // failing with GeneralFailure.
+        match std::str::from_utf8(self.cursor.get_ref()) {
+            Ok(s) => {
+                let content = s.to_string();
+                Box::pin(async move {
+                    fs.create_or_update_note(parent_id.as_deref(), &title, &syntax, &content)
+                        .map(|_| ())
+                })
+            }
+            Err(_) => {
+                let content = self.cursor.get_ref().clone();
+                let mime_type = guess_mime_type(&syntax).to_string();
+                Box::pin(async move {
+                    fs.create_or_update_blob(parent_id.as_deref(), &title, &syntax, &content, &mime_type)
+                        .map(|_| ())
+                })
+            }
         }
     }
 }
 
-/// Parse SQLite datetime string to SystemTime
-fn parse_datetime(s: &str) -> SystemTime {
-    use std::time::Duration;
-
-    let parts: Vec<&str> = s.split(&['-', ' ', ':'][..]).collect();
-    if parts.len() >= 6 {
-        if let (Ok(year), Ok(month), Ok(day), Ok(hour), Ok(min), Ok(sec)) = (
-            parts[0].parse::<i64>(),
-            parts[1].parse::<i64>(),
-            parts[2].parse::<i64>(),
-            parts[3].parse::<i64>(),
-            parts[4].parse::<i64>(),
-            parts[5].parse::<i64>(),
-        ) {
-            let days_since_epoch = (year - 1970) * 365 + (month - 1) * 30 + day;
-            let secs = (days_since_epoch * 86400 + hour * 3600 + min * 60 + sec) as u64;
-            return SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+/// A read-only WebDAV file serving pre-rendered bytes with no backing note
+/// and no write path (currently just the virtual Atom feed).
+pub struct FeedFile {
+    cursor: Cursor<Vec<u8>>,
+    len: u64,
+}
+
+impl FeedFile {
+    pub fn new(content: Vec<u8>) -> Self {
+        let len = content.len() as u64;
+        Self {
+            cursor: Cursor::new(content),
+            len,
         }
     }
-    SystemTime::now()
+}
+
+impl Debug for FeedFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeedFile").field("len", &self.len).finish()
+    }
+}
+
+impl DavFile for FeedFile {
+    fn metadata(&mut self) -> FsFuture<'_, Box<dyn DavMetaData>> {
+        let len = self.len;
+        let now = SystemTime::now();
+
+        Box::pin(async move {
+            Ok(Box::new(NoteMetaData {
+                len,
+                modified: now,
+                created: now,
+            }) as Box<dyn DavMetaData>)
+        })
+    }
+
+    fn read_bytes(&mut self, count: usize) -> FsFuture<'_, Bytes> {
+        use std::io::Read;
+
+        let mut buf = vec![0u8; count];
+        let n = self.cursor.read(&mut buf).unwrap_or(0);
+        buf.truncate(n);
+
+        Box::pin(async move { Ok(Bytes::from(buf)) })
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> FsFuture<'_, u64> {
+        use std::io::Seek;
+
+        let result = self.cursor.seek(pos).map_err(|_| FsError::GeneralFailure);
+        Box::pin(async move { result })
+    }
+
+    fn write_bytes(&mut self, _buf: Bytes) -> FsFuture<'_, ()> {
+        Box::pin(async { Err(FsError::Forbidden) })
+    }
+
+    fn write_buf(&mut self, _buf: Box<dyn Buf + Send>) -> FsFuture<'_, ()> {
+        Box::pin(async { Err(FsError::Forbidden) })
+    }
+
+    fn flush(&mut self) -> FsFuture<'_, ()> {
+        Box::pin(async { Ok(()) })
+    }
 }
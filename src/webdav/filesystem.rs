@@ -1,5 +1,5 @@
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::Path;
 use std::pin::Pin;
 use std::time::SystemTime;
 
@@ -9,33 +9,80 @@ use dav_server::fs::{
     FsStream, OpenOptions, ReadDirMeta,
 };
 use futures_util::stream;
+use http::StatusCode;
 use percent_encoding::percent_decode_str;
 use rusqlite::{params, Connection};
 use uuid::Uuid;
 
-use super::davfile::SqliteDavFile;
+use super::davfile::{FeedFile, SqliteDavFile};
+
+/// Connection pool shared across all filesystem requests for one database.
+pub type DbPool = r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>;
+
+/// A single pooled connection borrowed for the duration of one operation.
+type PooledConn = r2d2::PooledConnection<r2d2_sqlite::SqliteConnectionManager>;
+
+/// Build a WAL-mode connection pool for the database at `db_path`.
+///
+/// `foreign_keys` is re-enabled on every pooled connection (SQLite disables it
+/// by default, and CASCADE deletes rely on it), and WAL journaling plus a
+/// `busy_timeout` are set so concurrent WebDAV clients — one writing while
+/// others read directory listings — don't immediately hit `SQLITE_BUSY`.
+///
+/// The pool is capped at [`MAX_POOL_SIZE`] connections so a deep PROPFIND
+/// tree walk fanning out many requests at once can't exhaust file
+/// descriptors.
+pub fn build_pool(db_path: &Path) -> Result<DbPool, r2d2::Error> {
+    let manager = r2d2_sqlite::SqliteConnectionManager::file(db_path).with_init(|conn| {
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;",
+        )
+    });
+    r2d2::Pool::builder()
+        .max_size(MAX_POOL_SIZE)
+        .build(manager)
+}
+
+/// Upper bound on concurrent pooled connections (see [`build_pool`]).
+const MAX_POOL_SIZE: u32 = 16;
 
 /// A WebDAV filesystem backed by SQLite
 #[derive(Clone)]
 pub struct SqliteFs {
-    db_path: PathBuf,
+    pool: DbPool,
     user_id: String,
 }
 
 impl SqliteFs {
-    pub fn new(db_path: PathBuf, user_id: String) -> Self {
-        Self { db_path, user_id }
+    pub fn new(pool: DbPool, user_id: String) -> Self {
+        Self { pool, user_id }
     }
 
-    fn open_db(&self) -> FsResult<Connection> {
-        let conn = Connection::open(&self.db_path).map_err(|_| FsError::GeneralFailure)?;
-
-        // Enable foreign keys - SQLite has them disabled by default.
-        // This is required for ON DELETE CASCADE to work when deleting folders.
-        conn.execute("PRAGMA foreign_keys = ON", [])
-            .map_err(|_| FsError::GeneralFailure)?;
+    fn open_db(&self) -> FsResult<PooledConn> {
+        // Borrow a connection from the pool. Foreign keys, WAL mode, and the
+        // busy timeout were configured once at pool build time.
+        self.pool.get().map_err(|_| FsError::GeneralFailure)
+    }
 
-        Ok(conn)
+    /// Run `f` inside a single SQLite transaction, committing on success and
+    /// rolling back if the closure returns an error.
+    ///
+    /// All of the multi-step mutating operations go through this helper so that
+    /// a failure partway through (for example after deleting an overwrite
+    /// target but before the replacement `UPDATE` succeeds) can never leave the
+    /// database in a half-applied state. Helpers that themselves touch the
+    /// database take the `&Transaction` handle directly; none of them currently
+    /// need to roll back just their own step, so there is no nested
+    /// `SAVEPOINT` here — the whole closure is the unit of rollback.
+    fn with_tx<T, F>(&self, f: F) -> FsResult<T>
+    where
+        F: FnOnce(&rusqlite::Transaction) -> FsResult<T>,
+    {
+        let mut conn = self.open_db()?;
+        let tx = conn.transaction().map_err(|_| FsError::GeneralFailure)?;
+        let result = f(&tx)?;
+        tx.commit().map_err(|_| FsError::GeneralFailure)?;
+        Ok(result)
     }
 
     /// Resolve a path to (parent_folder_id, entry_name, is_file)
@@ -48,6 +95,11 @@ impl SqliteFs {
             return Ok(ResolvedPath::Root);
         }
 
+        // The Atom feed is a virtual, read-only file at the root.
+        if path_str == FEED_NAME {
+            return Ok(ResolvedPath::Feed);
+        }
+
         // URL-decode each path component
         let components: Vec<String> = path_str
             .split('/')
@@ -85,18 +137,260 @@ impl SqliteFs {
 
                 return Err(FsError::NotFound);
             } else {
-                // Not the last component - must be a folder
-                current_folder_id =
-                    self.find_folder(&conn, current_folder_id.as_deref(), component)?;
-                if current_folder_id.is_none() {
-                    return Err(FsError::NotFound);
+                // Not the last component - normally a folder, but a note may
+                // appear mid-path when the client is navigating into one of its
+                // relationship subfolders (`Note.md/<kind>/...`).
+                if let Some(folder_id) =
+                    self.find_folder(&conn, current_folder_id.as_deref(), component)?
+                {
+                    current_folder_id = Some(folder_id);
+                    continue;
                 }
+
+                if let Some((title, syntax)) = parse_filename(component) {
+                    if let Some(note_id) =
+                        self.find_note_id(&conn, current_folder_id.as_deref(), &title, &syntax)?
+                    {
+                        return self.resolve_relationship(&conn, &note_id, &components[i + 1..]);
+                    }
+                }
+
+                return Err(FsError::NotFound);
             }
         }
 
         Err(FsError::NotFound)
     }
 
+    /// Look up the MIME type stored for the note at `path` (root-relative,
+    /// `/`-separated, not yet URL-decoded).
+    ///
+    /// dav-server infers `Content-Type` from the path's extension, which
+    /// knows nothing about a binary note's own stored `mime_type`
+    /// (`create_or_update_blob`), so the HTTP layer calls this to override
+    /// that guess with the real one on GET. Returns `None` for folders,
+    /// unresolvable paths, or a note that has no stored MIME type (plain-text
+    /// notes, where the extension guess is already correct).
+    pub fn mime_type_for_path(&self, path: &str) -> FsResult<Option<String>> {
+        let path = path.trim_start_matches('/').trim_end_matches('/');
+        if path.is_empty() || path == FEED_NAME {
+            return Ok(None);
+        }
+
+        let components: Vec<String> = path
+            .split('/')
+            .map(|c| percent_decode_str(c).decode_utf8_lossy().to_string())
+            .collect();
+        let conn = self.open_db()?;
+
+        let mut current_folder_id: Option<String> = None;
+        for (i, component) in components.iter().enumerate() {
+            if i + 1 == components.len() {
+                let Some((title, syntax)) = parse_filename(component) else {
+                    return Ok(None);
+                };
+                let Some(note_id) =
+                    self.find_note_id(&conn, current_folder_id.as_deref(), &title, &syntax)?
+                else {
+                    return Ok(None);
+                };
+                return conn
+                    .query_row(
+                        "SELECT mime_type FROM notes WHERE id = ? AND user_id = ?",
+                        params![note_id, self.user_id],
+                        |row| row.get(0),
+                    )
+                    .map_err(|_| FsError::GeneralFailure);
+            }
+
+            match self.find_folder(&conn, current_folder_id.as_deref(), component)? {
+                Some(folder_id) => current_folder_id = Some(folder_id),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Resolve the part of a path that follows a note into its relationship
+    /// graph: `[<kind>]` names the synthetic folder of related notes, and
+    /// `[<kind>, <file>]` names one related note within it.
+    fn resolve_relationship(
+        &self,
+        conn: &Connection,
+        note_id: &str,
+        rest: &[String],
+    ) -> FsResult<ResolvedPath> {
+        match rest {
+            [kind] => Ok(ResolvedPath::RelationshipFolder {
+                note_id: note_id.to_string(),
+                kind: kind.clone(),
+            }),
+            [kind, file] => {
+                let (title, syntax) = parse_filename(file).ok_or(FsError::NotFound)?;
+                for related in self.list_related(conn, note_id, kind)? {
+                    if related.title == title && related.syntax == syntax {
+                        return Ok(ResolvedPath::Note {
+                            parent_id: related.parent_id,
+                            title,
+                            syntax,
+                        });
+                    }
+                }
+                Err(FsError::NotFound)
+            }
+            _ => Err(FsError::NotFound),
+        }
+    }
+
+    /// Look up the stable id of a note by location.
+    fn find_note_id(
+        &self,
+        conn: &Connection,
+        parent_id: Option<&str>,
+        title: &str,
+        syntax: &str,
+    ) -> FsResult<Option<String>> {
+        let result = if let Some(pid) = parent_id {
+            conn.query_row(
+                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                params![title, syntax, pid, self.user_id],
+                |row| row.get::<_, String>(0),
+            )
+        } else {
+            conn.query_row(
+                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                params![title, syntax, self.user_id],
+                |row| row.get::<_, String>(0),
+            )
+        };
+
+        match result {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(_) => Err(FsError::GeneralFailure),
+        }
+    }
+
+    /// Load the notes related to `note_id` by the given relationship `kind`,
+    /// following outbound edges (`src_note_id = note_id`).
+    fn list_related(&self, conn: &Connection, note_id: &str, kind: &str) -> FsResult<Vec<RelatedNote>> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT n.id, n.title, n.syntax, n.parent_id
+                 FROM note_relationships r
+                 JOIN notes n ON n.id = r.dst_note_id
+                 WHERE r.src_note_id = ? AND r.kind = ? AND n.user_id = ?",
+            )
+            .map_err(|_| FsError::GeneralFailure)?;
+        let rows = stmt
+            .query_map(params![note_id, kind, self.user_id], |row| {
+                Ok(RelatedNote {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    syntax: row.get(2)?,
+                    parent_id: row.get(3)?,
+                })
+            })
+            .map_err(|_| FsError::GeneralFailure)?;
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|_| FsError::GeneralFailure)
+    }
+
+    /// Load the `(dst_note_id, kind)` pairs for a note's outbound relationships.
+    pub fn outbound_relationships(&self, note_id: &str) -> FsResult<Vec<(String, String)>> {
+        let conn = self.open_db()?;
+        let mut stmt = conn
+            .prepare("SELECT dst_note_id, kind FROM note_relationships WHERE src_note_id = ?")
+            .map_err(|_| FsError::GeneralFailure)?;
+        let rows = stmt
+            .query_map(params![note_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|_| FsError::GeneralFailure)?;
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|_| FsError::GeneralFailure)
+    }
+
+    /// Load the `(src_note_id, kind)` pairs for a note's inbound relationships.
+    pub fn inbound_relationships(&self, note_id: &str) -> FsResult<Vec<(String, String)>> {
+        let conn = self.open_db()?;
+        let mut stmt = conn
+            .prepare("SELECT src_note_id, kind FROM note_relationships WHERE dst_note_id = ?")
+            .map_err(|_| FsError::GeneralFailure)?;
+        let rows = stmt
+            .query_map(params![note_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|_| FsError::GeneralFailure)?;
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|_| FsError::GeneralFailure)
+    }
+
+    /// Render the virtual Atom feed as UTF-8 bytes: the [`FEED_LIMIT`]
+    /// most-recently-updated notes, newest first, each mapped to an `<entry>`
+    /// whose `<link>` points at the note's WebDAV path.
+    fn render_feed(&self) -> FsResult<Vec<u8>> {
+        let conn = self.open_db()?;
+
+        // Pull the recent notes together with their folder path, built in SQL
+        // by walking parent_id up to the root.
+        let mut stmt = conn
+            .prepare(
+                "WITH RECURSIVE ancestry(note_id, title, syntax, updated_at, created_at, folder_id, path) AS (
+                     SELECT id, title, syntax, updated_at, created_at, parent_id, ''
+                     FROM notes WHERE user_id = ?1
+                     UNION ALL
+                     SELECT a.note_id, a.title, a.syntax, a.updated_at, a.created_at, f.parent_id,
+                            f.title || '/' || a.path
+                     FROM ancestry a JOIN folders f ON f.id = a.folder_id
+                 )
+                 SELECT note_id, title, syntax, updated_at, created_at, path
+                 FROM ancestry WHERE folder_id IS NULL
+                 ORDER BY updated_at DESC
+                 LIMIT ?2",
+            )
+            .map_err(|_| FsError::GeneralFailure)?;
+        let rows = stmt
+            .query_map(params![self.user_id, FEED_LIMIT as i64], |row| {
+                Ok(FeedRow {
+                    note_id: row.get(0)?,
+                    title: row.get(1)?,
+                    syntax: row.get(2)?,
+                    updated_at: row.get(3)?,
+                    created_at: row.get(4)?,
+                    folder_path: row.get(5)?,
+                })
+            })
+            .map_err(|_| FsError::GeneralFailure)?;
+        let notes: Vec<FeedRow> = rows
+            .collect::<rusqlite::Result<_>>()
+            .map_err(|_| FsError::GeneralFailure)?;
+
+        let feed_updated = notes
+            .first()
+            .map(|n| iso_date(&n.updated_at))
+            .unwrap_or_else(|| iso_date(&current_timestamp()));
+
+        let mut entries = String::new();
+        for n in &notes {
+            let href = format!("/{}{}.{}", n.folder_path, n.title, n.syntax);
+            entries.push_str(&format!(
+                "  <entry>\n    <id>urn:uuid:{}</id>\n    <title>{}</title>\n    <updated>{}</updated>\n    <published>{}</published>\n    <link rel=\"alternate\" href=\"{}\"/>\n  </entry>\n",
+                n.note_id,
+                xml_escape(&n.title),
+                iso_date(&n.updated_at),
+                iso_date(&n.created_at),
+                xml_escape(&percent_encode_path(&href)),
+            ));
+        }
+
+        let xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <id>urn:lilium:feed:{}</id>\n  <title>Recently updated notes</title>\n  <updated>{}</updated>\n{}</feed>\n",
+            xml_escape(&self.user_id),
+            feed_updated,
+            entries,
+        );
+
+        Ok(xml.into_bytes())
+    }
+
     fn find_folder(
         &self,
         conn: &Connection,
@@ -157,7 +451,7 @@ impl SqliteFs {
 
         let result = if let Some(pid) = parent_id {
             conn.query_row(
-                "SELECT id, title, content, syntax, created_at, updated_at FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                "SELECT id, title, content, syntax, created_at, updated_at, is_binary, content_blob, mime_type FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
                 params![title, syntax, pid, self.user_id],
                 |row| Ok(NoteData {
                     id: row.get(0)?,
@@ -166,11 +460,14 @@ impl SqliteFs {
                     syntax: row.get(3)?,
                     created_at: row.get(4)?,
                     updated_at: row.get(5)?,
+                    is_binary: row.get(6)?,
+                    content_blob: row.get(7)?,
+                    mime_type: row.get(8)?,
                 }),
             )
         } else {
             conn.query_row(
-                "SELECT id, title, content, syntax, created_at, updated_at FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                "SELECT id, title, content, syntax, created_at, updated_at, is_binary, content_blob, mime_type FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
                 params![title, syntax, self.user_id],
                 |row| Ok(NoteData {
                     id: row.get(0)?,
@@ -179,6 +476,9 @@ impl SqliteFs {
                     syntax: row.get(3)?,
                     created_at: row.get(4)?,
                     updated_at: row.get(5)?,
+                    is_binary: row.get(6)?,
+                    content_blob: row.get(7)?,
+                    mime_type: row.get(8)?,
                 }),
             )
         };
@@ -271,7 +571,7 @@ impl SqliteFs {
         let note_query = match parent_id {
             Some(pid) => {
                 let mut stmt = conn.prepare(
-                    "SELECT id, title, content, syntax, created_at, updated_at FROM notes WHERE parent_id = ? AND user_id = ?"
+                    "SELECT id, title, content, syntax, created_at, updated_at, is_binary, content_blob, mime_type FROM notes WHERE parent_id = ? AND user_id = ?"
                 ).map_err(|_| FsError::GeneralFailure)?;
                 let rows = stmt
                     .query_map(params![pid, self.user_id], |row| {
@@ -282,6 +582,9 @@ impl SqliteFs {
                             syntax: row.get(3)?,
                             created_at: row.get(4)?,
                             updated_at: row.get(5)?,
+                            is_binary: row.get(6)?,
+                            content_blob: row.get(7)?,
+                            mime_type: row.get(8)?,
                         })
                     })
                     .map_err(|_| FsError::GeneralFailure)?;
@@ -289,7 +592,7 @@ impl SqliteFs {
             }
             None => {
                 let mut stmt = conn.prepare(
-                    "SELECT id, title, content, syntax, created_at, updated_at FROM notes WHERE parent_id IS NULL AND user_id = ?"
+                    "SELECT id, title, content, syntax, created_at, updated_at, is_binary, content_blob, mime_type FROM notes WHERE parent_id IS NULL AND user_id = ?"
                 ).map_err(|_| FsError::GeneralFailure)?;
                 let rows = stmt
                     .query_map(params![self.user_id], |row| {
@@ -300,6 +603,9 @@ impl SqliteFs {
                             syntax: row.get(3)?,
                             created_at: row.get(4)?,
                             updated_at: row.get(5)?,
+                            is_binary: row.get(6)?,
+                            content_blob: row.get(7)?,
+                            mime_type: row.get(8)?,
                         })
                     })
                     .map_err(|_| FsError::GeneralFailure)?;
@@ -321,44 +627,97 @@ impl SqliteFs {
         syntax: &str,
         content: &str,
     ) -> FsResult<String> {
-        let conn = self.open_db()?;
         let timestamp = current_timestamp();
 
-        // Check if note already exists
-        let existing_id = if let Some(pid) = parent_id {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
-                params![title, syntax, pid, self.user_id],
-                |row| row.get::<_, String>(0),
-            )
-            .ok()
-        } else {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
-                params![title, syntax, self.user_id],
-                |row| row.get::<_, String>(0),
-            )
-            .ok()
-        };
+        self.with_tx(|tx| {
+            // Check if note already exists
+            let existing_id = if let Some(pid) = parent_id {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![title, syntax, pid, self.user_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .ok()
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![title, syntax, self.user_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .ok()
+            };
 
-        if let Some(id) = existing_id {
-            // Update existing note
-            conn.execute(
-                "UPDATE notes SET content = ?, updated_at = ? WHERE id = ?",
-                params![content, timestamp, id],
-            )
-            .map_err(|_| FsError::GeneralFailure)?;
-            Ok(id)
-        } else {
-            // Create new note
-            let id = Uuid::new_v4().to_string();
-            conn.execute(
-                "INSERT INTO notes (id, title, content, syntax, parent_id, user_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                params![id, title, content, syntax, parent_id, self.user_id, timestamp, timestamp],
-            )
-            .map_err(|_| FsError::GeneralFailure)?;
-            Ok(id)
-        }
+            if let Some(id) = existing_id {
+                // Update existing note, clearing any previously stored binary
+                // content so a text save always wins over a stale blob.
+                tx.execute(
+                    "UPDATE notes SET content = ?, updated_at = ?, is_binary = 0, content_blob = NULL, mime_type = NULL WHERE id = ?",
+                    params![content, timestamp, id],
+                )
+                .map_err(|_| FsError::GeneralFailure)?;
+                Ok(id)
+            } else {
+                // Create new note
+                let id = Uuid::new_v4().to_string();
+                tx.execute(
+                    "INSERT INTO notes (id, title, content, syntax, parent_id, user_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![id, title, content, syntax, parent_id, self.user_id, timestamp, timestamp],
+                )
+                .map_err(|_| FsError::GeneralFailure)?;
+                Ok(id)
+            }
+        })
+    }
+
+    /// Create or update a note with raw binary content (e.g. an image or PDF
+    /// uploaded over WebDAV), counterpart to [`Self::create_or_update_note`]
+    /// for content that isn't valid UTF-8. `content` stays empty; the bytes
+    /// live in `content_blob` instead, tagged with `mime_type` so a later GET
+    /// can serve them back as-is.
+    pub fn create_or_update_blob(
+        &self,
+        parent_id: Option<&str>,
+        title: &str,
+        syntax: &str,
+        content: &[u8],
+        mime_type: &str,
+    ) -> FsResult<String> {
+        let timestamp = current_timestamp();
+
+        self.with_tx(|tx| {
+            let existing_id = if let Some(pid) = parent_id {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![title, syntax, pid, self.user_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .ok()
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![title, syntax, self.user_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .ok()
+            };
+
+            if let Some(id) = existing_id {
+                tx.execute(
+                    "UPDATE notes SET content = '', updated_at = ?, is_binary = 1, content_blob = ?, mime_type = ? WHERE id = ?",
+                    params![timestamp, content, mime_type, id],
+                )
+                .map_err(|_| FsError::GeneralFailure)?;
+                Ok(id)
+            } else {
+                let id = Uuid::new_v4().to_string();
+                tx.execute(
+                    "INSERT INTO notes (id, title, content, syntax, parent_id, user_id, created_at, updated_at, is_binary, content_blob, mime_type) VALUES (?, ?, '', ?, ?, ?, ?, ?, 1, ?, ?)",
+                    params![id, title, syntax, parent_id, self.user_id, timestamp, timestamp, content, mime_type],
+                )
+                .map_err(|_| FsError::GeneralFailure)?;
+                Ok(id)
+            }
+        })
     }
 
     /// Create a new folder in the database
@@ -393,39 +752,54 @@ impl SqliteFs {
 
     /// Delete a note from the database
     pub fn delete_note(&self, parent_id: Option<&str>, title: &str, syntax: &str) -> FsResult<()> {
-        let conn = self.open_db()?;
+        self.with_tx(|tx| {
+            // Find the note ID first
+            let note_id: String = if let Some(pid) = parent_id {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![title, syntax, pid, self.user_id],
+                    |row| row.get(0),
+                )
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![title, syntax, self.user_id],
+                    |row| row.get(0),
+                )
+            }
+            .map_err(|e| {
+                eprintln!("[DELETE_NOTE] Note not found: {}", e);
+                FsError::NotFound
+            })?;
 
-        // Find the note ID first
-        let note_id: String = if let Some(pid) = parent_id {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
-                params![title, syntax, pid, self.user_id],
-                |row| row.get(0),
-            )
-        } else {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
-                params![title, syntax, self.user_id],
-                |row| row.get(0),
+            eprintln!("[DELETE_NOTE] Deleting note '{}' with id: {}", title, note_id);
+
+            // Drop relationship edges on both sides so no dangling graph rows
+            // survive the note. (CASCADE would also catch these, but we do not want
+            // to depend on the foreign-key pragma being enabled for correctness.)
+            tx.execute(
+                "DELETE FROM note_relationships WHERE src_note_id = ? OR dst_note_id = ?",
+                params![note_id, note_id],
             )
-        }
-        .map_err(|e| {
-            eprintln!("[DELETE_NOTE] Note not found: {}", e);
-            FsError::NotFound
-        })?;
+            .map_err(|e| {
+                eprintln!("[DELETE_NOTE] Failed to clear relationships: {}", e);
+                FsError::GeneralFailure
+            })?;
 
-        eprintln!("[DELETE_NOTE] Deleting note '{}' with id: {}", title, note_id);
+            tx.execute(
+                "DELETE FROM notes WHERE id = ? AND user_id = ?",
+                params![note_id, self.user_id],
+            )
+            .map_err(|e| {
+                eprintln!("[DELETE_NOTE] Database error: {}", e);
+                FsError::GeneralFailure
+            })?;
 
-        conn.execute(
-            "DELETE FROM notes WHERE id = ? AND user_id = ?",
-            params![note_id, self.user_id],
-        )
-        .map_err(|e| {
-            eprintln!("[DELETE_NOTE] Database error: {}", e);
-            FsError::GeneralFailure
-        })?;
+            // Drop any dead properties that were attached to this note.
+            self.delete_props(tx, &note_id)?;
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Delete a folder from the database.
@@ -437,42 +811,61 @@ impl SqliteFs {
     /// IMPORTANT: Foreign keys must be enabled (PRAGMA foreign_keys = ON) for CASCADE to work.
     /// This is done in open_db().
     pub fn delete_folder(&self, folder_id: &str) -> FsResult<()> {
-        let conn = self.open_db()?;
-
         eprintln!("[DELETE_FOLDER] Deleting folder with id: {}", folder_id);
 
-        // Verify the folder exists and belongs to this user
-        let exists: bool = conn
-            .query_row(
-                "SELECT 1 FROM folders WHERE id = ? AND user_id = ?",
-                params![folder_id, self.user_id],
-                |_| Ok(true),
-            )
-            .unwrap_or(false);
+        self.with_tx(|tx| {
+            // Verify the folder exists and belongs to this user
+            let exists: bool = tx
+                .query_row(
+                    "SELECT 1 FROM folders WHERE id = ? AND user_id = ?",
+                    params![folder_id, self.user_id],
+                    |_| Ok(true),
+                )
+                .unwrap_or(false);
 
-        if !exists {
-            eprintln!("[DELETE_FOLDER] Folder not found or not owned by user");
-            return Err(FsError::NotFound);
-        }
+            if !exists {
+                eprintln!("[DELETE_FOLDER] Folder not found or not owned by user");
+                return Err(FsError::NotFound);
+            }
 
-        // Delete the folder - CASCADE will handle children
-        let deleted = conn
-            .execute(
-                "DELETE FROM folders WHERE id = ? AND user_id = ?",
+            // Drop dead properties for the whole subtree (folders and their
+            // notes) before the rows disappear via CASCADE.
+            tx.execute(
+                "WITH RECURSIVE subtree(id) AS (
+                     SELECT id FROM folders WHERE id = ?1 AND user_id = ?2
+                     UNION ALL
+                     SELECT f.id FROM folders f JOIN subtree s ON f.parent_id = s.id
+                     WHERE f.user_id = ?2
+                 )
+                 DELETE FROM props WHERE entity_id IN (
+                     SELECT id FROM subtree
+                     UNION
+                     SELECT n.id FROM notes n
+                     WHERE n.parent_id IN (SELECT id FROM subtree) AND n.user_id = ?2
+                 )",
                 params![folder_id, self.user_id],
             )
-            .map_err(|e| {
-                eprintln!("[DELETE_FOLDER] Database error: {}", e);
-                FsError::GeneralFailure
-            })?;
+            .map_err(|_| FsError::GeneralFailure)?;
 
-        if deleted == 0 {
-            eprintln!("[DELETE_FOLDER] No rows deleted");
-            return Err(FsError::NotFound);
-        }
+            // Delete the folder - CASCADE will handle children
+            let deleted = tx
+                .execute(
+                    "DELETE FROM folders WHERE id = ? AND user_id = ?",
+                    params![folder_id, self.user_id],
+                )
+                .map_err(|e| {
+                    eprintln!("[DELETE_FOLDER] Database error: {}", e);
+                    FsError::GeneralFailure
+                })?;
 
-        eprintln!("[DELETE_FOLDER] Folder deleted successfully (CASCADE handled children)");
-        Ok(())
+            if deleted == 0 {
+                eprintln!("[DELETE_FOLDER] No rows deleted");
+                return Err(FsError::NotFound);
+            }
+
+            eprintln!("[DELETE_FOLDER] Folder deleted successfully (CASCADE handled children)");
+            Ok(())
+        })
     }
 
     /// Rename/move a note in the database
@@ -485,199 +878,680 @@ impl SqliteFs {
         dst_title: &str,
         dst_syntax: &str,
     ) -> FsResult<()> {
-        let conn = self.open_db()?;
         let timestamp = current_timestamp();
 
-        // Find the source note
-        let note_id: String = if let Some(pid) = src_parent_id {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
-                params![src_title, src_syntax, pid, self.user_id],
-                |row| row.get(0),
+        self.with_tx(|tx| {
+            // Find the source note
+            let note_id: String = if let Some(pid) = src_parent_id {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![src_title, src_syntax, pid, self.user_id],
+                    |row| row.get(0),
+                )
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![src_title, src_syntax, self.user_id],
+                    |row| row.get(0),
+                )
+            }
+            .map_err(|e| {
+                eprintln!("[RENAME_NOTE] Source note not found: {}", e);
+                FsError::NotFound
+            })?;
+
+            eprintln!(
+                "[RENAME_NOTE] Found source note id={}, renaming '{}.{}' -> '{}.{}'",
+                note_id, src_title, src_syntax, dst_title, dst_syntax
+            );
+
+            // Check if destination already exists and delete it (MOVE overwrites)
+            let existing_dst_id: Option<String> = if let Some(pid) = dst_parent_id {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![dst_title, dst_syntax, pid, self.user_id],
+                    |row| row.get(0),
+                )
+                .ok()
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![dst_title, dst_syntax, self.user_id],
+                    |row| row.get(0),
+                )
+                .ok()
+            };
+
+            if let Some(dst_id) = existing_dst_id {
+                if dst_id != note_id {
+                    eprintln!("[RENAME_NOTE] Overwriting existing note at destination: {}", dst_id);
+                    tx.execute(
+                        "DELETE FROM notes WHERE id = ? AND user_id = ?",
+                        params![dst_id, self.user_id],
+                    )
+                    .map_err(|e| {
+                        eprintln!("[RENAME_NOTE] Failed to delete destination: {}", e);
+                        FsError::GeneralFailure
+                    })?;
+                }
+            }
+
+            // Update the note with new title, syntax, and parent_id
+            tx.execute(
+                "UPDATE notes SET title = ?, syntax = ?, parent_id = ?, updated_at = ? WHERE id = ? AND user_id = ?",
+                params![dst_title, dst_syntax, dst_parent_id, timestamp, note_id, self.user_id],
             )
-        } else {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
-                params![src_title, src_syntax, self.user_id],
-                |row| row.get(0),
+            .map_err(|e| {
+                eprintln!("[RENAME_NOTE] Database error: {}", e);
+                FsError::GeneralFailure
+            })?;
+
+            eprintln!("[RENAME_NOTE] Note renamed successfully");
+            Ok(())
+        })
+    }
+
+    /// Rename/move a folder in the database
+    pub fn rename_folder(
+        &self,
+        folder_id: &str,
+        new_parent_id: Option<&str>,
+        new_title: &str,
+    ) -> FsResult<()> {
+        let timestamp = current_timestamp();
+
+        self.with_tx(|tx| {
+            // Verify the source folder exists and belongs to this user
+            let exists: bool = tx
+                .query_row(
+                    "SELECT 1 FROM folders WHERE id = ? AND user_id = ?",
+                    params![folder_id, self.user_id],
+                    |_| Ok(true),
+                )
+                .unwrap_or(false);
+
+            if !exists {
+                eprintln!("[RENAME_FOLDER] Source folder not found: {}", folder_id);
+                return Err(FsError::NotFound);
+            }
+
+            // Check if destination folder name already exists in target parent
+            let existing_dst = self.find_folder(tx, new_parent_id, new_title)?;
+
+            if let Some(dst_id) = existing_dst {
+                if dst_id != folder_id {
+                    // Destination folder already exists and is different from source
+                    // Unlike files, we don't overwrite folders - return error
+                    eprintln!(
+                        "[RENAME_FOLDER] Destination folder '{}' already exists",
+                        new_title
+                    );
+                    return Err(FsError::Exists);
+                }
+                // If dst_id == folder_id, it's a no-op (renaming to same name)
+            }
+
+            // Prevent moving a folder into itself or its descendants
+            if let Some(new_pid) = new_parent_id {
+                if new_pid == folder_id {
+                    eprintln!("[RENAME_FOLDER] Cannot move folder into itself");
+                    return Err(FsError::Forbidden);
+                }
+                // Check if new_parent_id is a descendant of folder_id
+                if self.is_descendant(tx, new_pid, folder_id)? {
+                    eprintln!("[RENAME_FOLDER] Cannot move folder into its own descendant");
+                    return Err(FsError::Forbidden);
+                }
+            }
+
+            eprintln!(
+                "[RENAME_FOLDER] Renaming folder {} to '{}' with parent {:?}",
+                folder_id, new_title, new_parent_id
+            );
+
+            // Update the folder
+            tx.execute(
+                "UPDATE folders SET title = ?, parent_id = ?, updated_at = ? WHERE id = ? AND user_id = ?",
+                params![new_title, new_parent_id, timestamp, folder_id, self.user_id],
             )
-        }
-        .map_err(|e| {
-            eprintln!("[RENAME_NOTE] Source note not found: {}", e);
-            FsError::NotFound
-        })?;
+            .map_err(|e| {
+                eprintln!("[RENAME_FOLDER] Database error: {}", e);
+                FsError::GeneralFailure
+            })?;
 
-        eprintln!(
-            "[RENAME_NOTE] Found source note id={}, renaming '{}.{}' -> '{}.{}'",
-            note_id, src_title, src_syntax, dst_title, dst_syntax
+            eprintln!("[RENAME_FOLDER] Folder renamed successfully");
+            Ok(())
+        })
+    }
+
+    /// Check if potential_descendant is a descendant of ancestor_id.
+    ///
+    /// Answered in a single round-trip with a recursive CTE that walks the
+    /// parent pointers upward from `potential_descendant` and checks whether
+    /// `ancestor_id` appears on the way to the root.
+    fn is_descendant(
+        &self,
+        conn: &Connection,
+        potential_descendant: &str,
+        ancestor_id: &str,
+    ) -> FsResult<bool> {
+        let result = conn.query_row(
+            "WITH RECURSIVE anc(id, parent_id) AS (
+                 SELECT id, parent_id FROM folders WHERE id = ?1 AND user_id = ?3
+                 UNION ALL
+                 SELECT f.id, f.parent_id FROM folders f
+                 JOIN anc a ON f.id = a.parent_id
+                 WHERE f.user_id = ?3
+             )
+             SELECT 1 FROM anc WHERE id = ?2 LIMIT 1",
+            params![potential_descendant, ancestor_id, self.user_id],
+            |_| Ok(()),
         );
 
-        // Check if destination already exists and delete it (MOVE overwrites)
-        let existing_dst_id: Option<String> = if let Some(pid) = dst_parent_id {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
-                params![dst_title, dst_syntax, pid, self.user_id],
-                |row| row.get(0),
-            )
-            .ok()
-        } else {
-            conn.query_row(
-                "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
-                params![dst_title, dst_syntax, self.user_id],
-                |row| row.get(0),
-            )
-            .ok()
-        };
+        match result {
+            Ok(()) => Ok(true),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
+            Err(_) => Err(FsError::GeneralFailure),
+        }
+    }
 
-        if let Some(dst_id) = existing_dst_id {
-            if dst_id != note_id {
-                eprintln!("[RENAME_NOTE] Overwriting existing note at destination: {}", dst_id);
-                conn.execute(
-                    "DELETE FROM notes WHERE id = ? AND user_id = ?",
+    /// Recursively copy the folder subtree rooted at `src_id` under
+    /// `dst_parent_id`, giving the copied root the title `new_title`.
+    ///
+    /// The whole copy runs inside one transaction. A downward recursive CTE
+    /// enumerates every folder in the subtree (parents before children); each
+    /// copied folder and note is given a fresh UUID, and children are re-parented
+    /// onto the new ids via an old-id → new-id mapping. Returns the id of the
+    /// newly created root folder.
+    pub fn copy_folder(
+        &self,
+        src_id: &str,
+        dst_parent_id: Option<&str>,
+        new_title: &str,
+    ) -> FsResult<String> {
+        self.with_tx(|tx| {
+            // Enumerate the folder subtree, ordered so a parent is always seen
+            // before its descendants.
+            let folders: Vec<(String, Option<String>, String, String, String)> = {
+                let mut stmt = tx
+                    .prepare(
+                        "WITH RECURSIVE sub(id, parent_id, title, created_at, updated_at, depth) AS (
+                             SELECT id, parent_id, title, created_at, updated_at, 0
+                             FROM folders WHERE id = ?1 AND user_id = ?2
+                             UNION ALL
+                             SELECT f.id, f.parent_id, f.title, f.created_at, f.updated_at, s.depth + 1
+                             FROM folders f JOIN sub s ON f.parent_id = s.id
+                             WHERE f.user_id = ?2
+                         )
+                         SELECT id, parent_id, title, created_at, updated_at FROM sub ORDER BY depth",
+                    )
+                    .map_err(|_| FsError::GeneralFailure)?;
+                let rows = stmt
+                    .query_map(params![src_id, self.user_id], |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                        ))
+                    })
+                    .map_err(|_| FsError::GeneralFailure)?;
+                rows.collect::<rusqlite::Result<_>>()
+                    .map_err(|_| FsError::GeneralFailure)?
+            };
+
+            if folders.is_empty() {
+                return Err(FsError::NotFound);
+            }
+
+            // COPY overwrites a pre-existing destination folder, mirroring
+            // `copy_note`. There is no unique (parent_id, title) constraint, so
+            // without this a same-named destination would end up duplicated.
+            let existing_dst_id: Option<String> = if let Some(pid) = dst_parent_id {
+                tx.query_row(
+                    "SELECT id FROM folders WHERE title = ? AND parent_id = ? AND user_id = ?",
+                    params![new_title, pid, self.user_id],
+                    |row| row.get(0),
+                )
+                .ok()
+            } else {
+                tx.query_row(
+                    "SELECT id FROM folders WHERE title = ? AND parent_id IS NULL AND user_id = ?",
+                    params![new_title, self.user_id],
+                    |row| row.get(0),
+                )
+                .ok()
+            };
+
+            if let Some(dst_id) = existing_dst_id {
+                eprintln!("[COPY_FOLDER] Overwriting existing folder at destination: {}", dst_id);
+                tx.execute(
+                    "WITH RECURSIVE subtree(id) AS (
+                         SELECT id FROM folders WHERE id = ?1 AND user_id = ?2
+                         UNION ALL
+                         SELECT f.id FROM folders f JOIN subtree s ON f.parent_id = s.id
+                         WHERE f.user_id = ?2
+                     )
+                     DELETE FROM props WHERE entity_id IN (
+                         SELECT id FROM subtree
+                         UNION
+                         SELECT n.id FROM notes n
+                         WHERE n.parent_id IN (SELECT id FROM subtree) AND n.user_id = ?2
+                     )",
+                    params![dst_id, self.user_id],
+                )
+                .map_err(|_| FsError::GeneralFailure)?;
+
+                tx.execute(
+                    "DELETE FROM folders WHERE id = ? AND user_id = ?",
                     params![dst_id, self.user_id],
                 )
                 .map_err(|e| {
-                    eprintln!("[RENAME_NOTE] Failed to delete destination: {}", e);
+                    eprintln!("[COPY_FOLDER] Failed to delete destination: {}", e);
                     FsError::GeneralFailure
                 })?;
             }
-        }
 
-        // Update the note with new title, syntax, and parent_id
-        conn.execute(
-            "UPDATE notes SET title = ?, syntax = ?, parent_id = ?, updated_at = ? WHERE id = ? AND user_id = ?",
-            params![dst_title, dst_syntax, dst_parent_id, timestamp, note_id, self.user_id],
-        )
-        .map_err(|e| {
-            eprintln!("[RENAME_NOTE] Database error: {}", e);
-            FsError::GeneralFailure
-        })?;
+            // Copy folders, building the old-id -> new-id map as we go.
+            let mut id_map: std::collections::HashMap<String, String> =
+                std::collections::HashMap::new();
+            for (old_id, old_parent, title, created_at, updated_at) in &folders {
+                let new_id = Uuid::new_v4().to_string();
+                let (new_parent, copied_title) = if old_id == src_id {
+                    (dst_parent_id.map(|s| s.to_string()), new_title.to_string())
+                } else {
+                    let mapped_parent = old_parent
+                        .as_deref()
+                        .and_then(|p| id_map.get(p))
+                        .cloned();
+                    (mapped_parent, title.clone())
+                };
+
+                tx.execute(
+                    "INSERT INTO folders (id, title, parent_id, user_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+                    params![new_id, copied_title, new_parent, self.user_id, created_at, updated_at],
+                )
+                .map_err(|_| FsError::GeneralFailure)?;
 
-        eprintln!("[RENAME_NOTE] Note renamed successfully");
-        Ok(())
+                id_map.insert(old_id.clone(), new_id);
+            }
+
+            // Copy the notes living directly under each copied folder.
+            for (old_folder_id, new_folder_id) in id_map
+                .iter()
+                .map(|(o, n)| (o.clone(), n.clone()))
+                .collect::<Vec<_>>()
+            {
+                #[allow(clippy::type_complexity)]
+                let notes: Vec<(
+                    String,
+                    String,
+                    String,
+                    String,
+                    String,
+                    bool,
+                    Option<Vec<u8>>,
+                    Option<String>,
+                )> = {
+                    let mut stmt = tx
+                        .prepare(
+                            "SELECT title, content, syntax, created_at, updated_at, is_binary, content_blob, mime_type FROM notes WHERE parent_id = ?1 AND user_id = ?2",
+                        )
+                        .map_err(|_| FsError::GeneralFailure)?;
+                    let rows = stmt
+                        .query_map(params![old_folder_id, self.user_id], |row| {
+                            Ok((
+                                row.get(0)?,
+                                row.get(1)?,
+                                row.get(2)?,
+                                row.get(3)?,
+                                row.get(4)?,
+                                row.get(5)?,
+                                row.get(6)?,
+                                row.get(7)?,
+                            ))
+                        })
+                        .map_err(|_| FsError::GeneralFailure)?;
+                    rows.collect::<rusqlite::Result<_>>()
+                        .map_err(|_| FsError::GeneralFailure)?
+                };
+
+                for (title, content, syntax, created_at, updated_at, is_binary, content_blob, mime_type) in notes {
+                    let note_id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO notes (id, title, content, syntax, parent_id, user_id, created_at, updated_at, is_binary, content_blob, mime_type) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        params![note_id, title, content, syntax, new_folder_id, self.user_id, created_at, updated_at, is_binary, content_blob, mime_type],
+                    )
+                    .map_err(|_| FsError::GeneralFailure)?;
+                }
+            }
+
+            id_map
+                .get(src_id)
+                .cloned()
+                .ok_or(FsError::GeneralFailure)
+        })
     }
 
-    /// Rename/move a folder in the database
-    pub fn rename_folder(
+    /// Copy a single note to a new location, giving the copy a fresh id and
+    /// timestamps. Overwrites any existing note at the destination, mirroring
+    /// `rename_note`'s overwrite behavior.
+    pub fn copy_note(
         &self,
-        folder_id: &str,
-        new_parent_id: Option<&str>,
-        new_title: &str,
+        src_parent_id: Option<&str>,
+        src_title: &str,
+        src_syntax: &str,
+        dst_parent_id: Option<&str>,
+        dst_title: &str,
+        dst_syntax: &str,
     ) -> FsResult<()> {
-        let conn = self.open_db()?;
         let timestamp = current_timestamp();
 
-        // Verify the source folder exists and belongs to this user
-        let exists: bool = conn
-            .query_row(
-                "SELECT 1 FROM folders WHERE id = ? AND user_id = ?",
-                params![folder_id, self.user_id],
-                |_| Ok(true),
+        self.with_tx(|tx| {
+            let (content, is_binary, content_blob, mime_type): (
+                String,
+                bool,
+                Option<Vec<u8>>,
+                Option<String>,
+            ) = if let Some(pid) = src_parent_id {
+                tx.query_row(
+                    "SELECT content, is_binary, content_blob, mime_type FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![src_title, src_syntax, pid, self.user_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+            } else {
+                tx.query_row(
+                    "SELECT content, is_binary, content_blob, mime_type FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![src_title, src_syntax, self.user_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+            }
+            .map_err(|e| {
+                eprintln!("[COPY_NOTE] Source note not found: {}", e);
+                FsError::NotFound
+            })?;
+
+            // COPY overwrites an existing destination note.
+            let existing_dst_id: Option<String> = if let Some(pid) = dst_parent_id {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id = ? AND user_id = ?",
+                    params![dst_title, dst_syntax, pid, self.user_id],
+                    |row| row.get(0),
+                )
+                .ok()
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notes WHERE title = ? AND syntax = ? AND parent_id IS NULL AND user_id = ?",
+                    params![dst_title, dst_syntax, self.user_id],
+                    |row| row.get(0),
+                )
+                .ok()
+            };
+
+            if let Some(dst_id) = existing_dst_id {
+                eprintln!("[COPY_NOTE] Overwriting existing note at destination: {}", dst_id);
+                tx.execute(
+                    "DELETE FROM notes WHERE id = ? AND user_id = ?",
+                    params![dst_id, self.user_id],
+                )
+                .map_err(|e| {
+                    eprintln!("[COPY_NOTE] Failed to delete destination: {}", e);
+                    FsError::GeneralFailure
+                })?;
+            }
+
+            let new_id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO notes (id, title, content, syntax, parent_id, user_id, created_at, updated_at, is_binary, content_blob, mime_type) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![new_id, dst_title, content, dst_syntax, dst_parent_id, self.user_id, timestamp, timestamp, is_binary, content_blob, mime_type],
             )
-            .unwrap_or(false);
+            .map_err(|e| {
+                eprintln!("[COPY_NOTE] Database error: {}", e);
+                FsError::GeneralFailure
+            })?;
+
+            eprintln!("[COPY_NOTE] Note copied successfully");
+            Ok(())
+        })
+    }
+
+    /// Resolve a path to the stable id of the note or folder it names, together
+    /// with its live metadata. Returns `None` for the root, which carries no
+    /// stored properties.
+    fn entity_for_path(&self, path: &DavPath) -> FsResult<Option<Entity>> {
+        match self.resolve_path(path)? {
+            ResolvedPath::Root => Ok(None),
+            ResolvedPath::Folder { id } => Ok(Some(Entity::Folder(self.get_folder_meta(&id)?))),
+            ResolvedPath::Note {
+                parent_id,
+                title,
+                syntax,
+            } => Ok(Some(Entity::Note(
+                self.get_note(parent_id.as_deref(), &title, &syntax)?,
+            ))),
+            // Synthetic relationship folders are views, not stored entities, so
+            // they carry no properties of their own.
+            ResolvedPath::RelationshipFolder { .. } => Ok(None),
+            // The virtual feed is generated on the fly, not a stored entity.
+            ResolvedPath::Feed => Ok(None),
+        }
+    }
+
+    /// Read the dead (client-supplied) properties stored for `entity_id`.
+    fn load_dead_props(&self, conn: &Connection, entity_id: &str) -> FsResult<Vec<DavProp>> {
+        let mut stmt = conn
+            .prepare("SELECT namespace, name, value FROM props WHERE entity_id = ?")
+            .map_err(|_| FsError::GeneralFailure)?;
+        let rows = stmt
+            .query_map(params![entity_id], |row| {
+                let namespace: String = row.get(0)?;
+                let name: String = row.get(1)?;
+                let value: Option<Vec<u8>> = row.get(2)?;
+                Ok(DavProp {
+                    namespace: if namespace.is_empty() {
+                        None
+                    } else {
+                        Some(namespace)
+                    },
+                    name,
+                    prefix: None,
+                    xml: value,
+                })
+            })
+            .map_err(|_| FsError::GeneralFailure)?;
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|_| FsError::GeneralFailure)
+    }
+
+    /// Delete every stored property row belonging to `entity_id`.
+    fn delete_props(&self, conn: &Connection, entity_id: &str) -> FsResult<()> {
+        conn.execute("DELETE FROM props WHERE entity_id = ?", params![entity_id])
+            .map(|_| ())
+            .map_err(|_| FsError::GeneralFailure)
+    }
+}
 
-        if !exists {
-            eprintln!("[RENAME_FOLDER] Source folder not found: {}", folder_id);
-            return Err(FsError::NotFound);
-        }
+/// Namespace used for the live properties synthesized from a note or folder's
+/// own columns (syntax, timestamps, stable id).
+const LIVE_NS: &str = "https://lilium.dev/webdav/";
+
+/// The standard WebDAV namespace, used for the RFC 4918 live properties
+/// (`resourcetype`, `getcontentlength`, `getlastmodified`, `creationdate`).
+const DAV_NS: &str = "DAV:";
+
+/// Build a live property in the standard `DAV:` namespace. `xml` is the raw
+/// element body (which for `resourcetype` is itself markup).
+fn dav_prop(name: &str, xml: &str) -> DavProp {
+    DavProp {
+        namespace: Some(DAV_NS.to_string()),
+        name: name.to_string(),
+        prefix: None,
+        xml: Some(xml.as_bytes().to_vec()),
+    }
+}
 
-        // Check if destination folder name already exists in target parent
-        let existing_dst = self.find_folder(&conn, new_parent_id, new_title)?;
+/// Format a stored `"YYYY-MM-DD HH:MM:SS"` timestamp as an RFC 1123 HTTP-date
+/// (e.g. `Wed, 15 Nov 1995 04:58:08 GMT`), as required by `getlastmodified`.
+/// Returns the input unchanged if it does not parse.
+fn http_date(stored: &str) -> String {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
 
-        if let Some(dst_id) = existing_dst {
-            if dst_id != folder_id {
-                // Destination folder already exists and is different from source
-                // Unlike files, we don't overwrite folders - return error
-                eprintln!(
-                    "[RENAME_FOLDER] Destination folder '{}' already exists",
-                    new_title
+    let parts: Vec<&str> = stored.split(&['-', ' ', ':'][..]).collect();
+    if parts.len() >= 6 {
+        if let (Ok(y), Ok(mo), Ok(d), Ok(h), Ok(mi), Ok(s)) = (
+            parts[0].parse::<i64>(),
+            parts[1].parse::<i64>(),
+            parts[2].parse::<i64>(),
+            parts[3].parse::<i64>(),
+            parts[4].parse::<i64>(),
+            parts[5].parse::<i64>(),
+        ) {
+            if (1..=12).contains(&mo) {
+                // 1970-01-01 is a Thursday; weekday = (days + 4) mod 7.
+                let dow = (days_from_civil(y, mo, d) + 4).rem_euclid(7) as usize;
+                return format!(
+                    "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+                    WEEKDAYS[dow],
+                    d,
+                    MONTHS[(mo - 1) as usize],
+                    y,
+                    h,
+                    mi,
+                    s
                 );
-                return Err(FsError::Exists);
             }
-            // If dst_id == folder_id, it's a no-op (renaming to same name)
         }
+    }
+    stored.to_string()
+}
 
-        // Prevent moving a folder into itself or its descendants
-        if let Some(new_pid) = new_parent_id {
-            if new_pid == folder_id {
-                eprintln!("[RENAME_FOLDER] Cannot move folder into itself");
-                return Err(FsError::Forbidden);
-            }
-            // Check if new_parent_id is a descendant of folder_id
-            if self.is_descendant(&conn, new_pid, folder_id)? {
-                eprintln!("[RENAME_FOLDER] Cannot move folder into its own descendant");
-                return Err(FsError::Forbidden);
-            }
-        }
+/// Format a stored `"YYYY-MM-DD HH:MM:SS"` timestamp as an ISO 8601 instant
+/// (e.g. `1995-11-15T04:58:08Z`), as required by `creationdate`.
+fn iso_date(stored: &str) -> String {
+    let parts: Vec<&str> = stored.splitn(2, ' ').collect();
+    match parts.as_slice() {
+        [date, time] => format!("{}T{}Z", date, time),
+        _ => stored.to_string(),
+    }
+}
 
-        eprintln!(
-            "[RENAME_FOLDER] Renaming folder {} to '{}' with parent {:?}",
-            folder_id, new_title, new_parent_id
-        );
+/// Minimally escape text for inclusion in XML character data.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
 
-        // Update the folder
-        conn.execute(
-            "UPDATE folders SET title = ?, parent_id = ?, updated_at = ? WHERE id = ? AND user_id = ?",
-            params![new_title, new_parent_id, timestamp, folder_id, self.user_id],
-        )
-        .map_err(|e| {
-            eprintln!("[RENAME_FOLDER] Database error: {}", e);
-            FsError::GeneralFailure
-        })?;
+/// Characters that must be percent-encoded within a single path segment of a
+/// feed entry's `href`. Leaves unreserved characters alone and keeps `/` as
+/// the segment separator.
+const PATH_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+
+/// Percent-encode a `/`-separated DAV path so titles containing spaces,
+/// `#`, or `?` still produce a resolvable `href`.
+fn percent_encode_path(path: &str) -> String {
+    percent_encoding::utf8_percent_encode(path, PATH_SEGMENT).to_string()
+}
 
-        eprintln!("[RENAME_FOLDER] Folder renamed successfully");
-        Ok(())
+/// Build a single live property with the [`LIVE_NS`] namespace.
+fn live_prop(name: &str, value: &str) -> DavProp {
+    DavProp {
+        namespace: Some(LIVE_NS.to_string()),
+        name: name.to_string(),
+        prefix: Some("L".to_string()),
+        xml: Some(value.as_bytes().to_vec()),
     }
+}
 
-    /// Check if potential_descendant is a descendant of ancestor_id
-    fn is_descendant(
-        &self,
-        conn: &Connection,
-        potential_descendant: &str,
-        ancestor_id: &str,
-    ) -> FsResult<bool> {
-        let mut current_id = Some(potential_descendant.to_string());
+/// A resolved note or folder, carrying its stable id and live metadata.
+enum Entity {
+    Folder(FolderData),
+    Note(NoteData),
+}
 
-        while let Some(id) = current_id {
-            if id == ancestor_id {
-                return Ok(true);
-            }
-            // Get parent of current folder
-            current_id = conn
-                .query_row(
-                    "SELECT parent_id FROM folders WHERE id = ? AND user_id = ?",
-                    params![id, self.user_id],
-                    |row| row.get::<_, Option<String>>(0),
-                )
-                .ok()
-                .flatten();
+impl Entity {
+    fn id(&self) -> &str {
+        match self {
+            Entity::Folder(f) => &f.id,
+            Entity::Note(n) => &n.id,
         }
+    }
 
-        Ok(false)
+    /// The live properties synthesized from this entity's own columns: the
+    /// standard `DAV:` set (`resourcetype`, `getcontentlength`,
+    /// `getlastmodified`, `creationdate`) plus our own `lilium` metadata.
+    fn live_props(&self) -> Vec<DavProp> {
+        match self {
+            Entity::Folder(f) => vec![
+                dav_prop("resourcetype", "<D:collection/>"),
+                dav_prop("getcontentlength", "0"),
+                dav_prop("getlastmodified", &http_date(&f.updated_at)),
+                dav_prop("creationdate", &iso_date(&f.created_at)),
+                live_prop("id", &f.id),
+                live_prop("created_at", &f.created_at),
+                live_prop("updated_at", &f.updated_at),
+            ],
+            Entity::Note(n) => vec![
+                dav_prop("resourcetype", ""),
+                dav_prop("getcontentlength", &n.len().to_string()),
+                dav_prop("getlastmodified", &http_date(&n.updated_at)),
+                dav_prop("creationdate", &iso_date(&n.created_at)),
+                live_prop("id", &n.id),
+                live_prop("syntax", &n.syntax),
+                live_prop("created_at", &n.created_at),
+                live_prop("updated_at", &n.updated_at),
+            ],
+        }
     }
 }
 
-/// Get current timestamp in SQLite format
+/// Convert a civil date to the number of days since the Unix epoch
+/// (1970-01-01), using Howard Hinnant's days-from-civil algorithm. Valid for
+/// any proleptic Gregorian date.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of [`days_from_civil`]: turn a day count since the Unix epoch back
+/// into a `(year, month, day)` civil date.
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+/// Get current timestamp in SQLite format.
 fn current_timestamp() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    let duration = SystemTime::now()
+    let secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .unwrap();
-
-    let secs = duration.as_secs();
-    let days = secs / 86400;
-    let remaining = secs % 86400;
-    let hours = remaining / 3600;
-    let remaining = remaining % 3600;
-    let minutes = remaining / 60;
-    let seconds = remaining % 60;
-
-    // Approximate date calculation (not accounting for leap years properly)
-    let year = 1970 + (days / 365);
-    let day_of_year = days % 365;
-    let month = 1 + (day_of_year / 30);
-    let day = 1 + (day_of_year % 30);
+        .unwrap()
+        .as_secs() as i64;
+
+    let days = secs.div_euclid(86400);
+    let tod = secs.rem_euclid(86400);
+    let (hours, minutes, seconds) = (tod / 3600, (tod % 3600) / 60, tod % 60);
+    let (year, month, day) = civil_from_days(days);
 
     format!(
         "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
@@ -695,22 +1569,85 @@ enum ResolvedPath {
         title: String,
         syntax: String,
     },
+    /// The synthetic `<kind>` subfolder under a note, listing the notes it is
+    /// related to by that relationship kind.
+    RelationshipFolder {
+        note_id: String,
+        kind: String,
+    },
+    /// The virtual, read-only Atom feed at `/.feed.atom`.
+    Feed,
+}
+
+/// Name of the virtual Atom feed resource at the root.
+const FEED_NAME: &str = ".feed.atom";
+
+/// Number of most-recently-updated notes included in the feed.
+const FEED_LIMIT: usize = 50;
+
+/// A recent note plus its folder path, used to build one Atom `<entry>`.
+struct FeedRow {
+    note_id: String,
+    title: String,
+    syntax: String,
+    updated_at: String,
+    created_at: String,
+    /// Folder prefix ending in `/` (empty for root-level notes).
+    folder_path: String,
+}
+
+/// A note reached by following a relationship edge.
+struct RelatedNote {
+    #[allow(unused)]
+    id: String,
+    title: String,
+    syntax: String,
+    parent_id: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 pub struct NoteData {
-    #[allow(unused)]
     pub id: String,
     pub title: String,
     pub content: String,
     pub syntax: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Whether this note's bytes live in `content_blob` (e.g. an uploaded
+    /// image or PDF) rather than `content` (plain text).
+    pub is_binary: bool,
+    /// Raw bytes for a binary note; `None` for plain-text notes.
+    pub content_blob: Option<Vec<u8>>,
+    /// MIME type recorded for a binary note, e.g. `image/png`.
+    pub mime_type: Option<String>,
+}
+
+impl NoteData {
+    /// The note's bytes, whichever column they're stored in.
+    pub fn bytes(&self) -> Vec<u8> {
+        if self.is_binary {
+            self.content_blob.clone().unwrap_or_default()
+        } else {
+            self.content.clone().into_bytes()
+        }
+    }
+
+    /// The note's content length in bytes, whichever column backs it.
+    pub fn len(&self) -> u64 {
+        if self.is_binary {
+            self.content_blob.as_ref().map_or(0, Vec::len) as u64
+        } else {
+            self.content.len() as u64
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 #[derive(Clone)]
 struct FolderData {
-    #[allow(unused)]
     id: String,
     title: String,
     created_at: String,
@@ -732,6 +1669,22 @@ fn parse_filename(name: &str) -> Option<(String, String)> {
     }
 }
 
+/// Guess a MIME type from a note's syntax/extension for storing alongside
+/// binary content; falls back to a generic octet-stream for anything
+/// unrecognized, same as a real filesystem serving unknown extensions.
+pub fn guess_mime_type(syntax: &str) -> &'static str {
+    match syntax.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
 /// Metadata for files/folders
 #[derive(Clone, Debug)]
 struct SqliteMetaData {
@@ -739,6 +1692,9 @@ struct SqliteMetaData {
     len: u64,
     modified: SystemTime,
     created: SystemTime,
+    /// `None` for synthetic entries (root, relationship folders, the feed)
+    /// that have no stable id to hash.
+    etag: Option<String>,
 }
 
 impl DavMetaData for SqliteMetaData {
@@ -757,6 +1713,43 @@ impl DavMetaData for SqliteMetaData {
     fn created(&self) -> FsResult<SystemTime> {
         Ok(self.created)
     }
+
+    fn etag(&self) -> Option<String> {
+        self.etag.clone()
+    }
+}
+
+/// A short, deterministic, non-cryptographic hash (FNV-1a, 64-bit). Used only
+/// to build compact ETags, never for anything security-sensitive.
+fn fnv1a(data: &[u8]) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET;
+    for &b in data {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Derive a stable ETag for a note or folder from its id and `updated_at`,
+/// so the tag is identical across restarts and changes exactly when the
+/// entity's mutation state does.
+///
+/// `updated_at` only has second resolution, so a write landing in the same
+/// wall-clock second the server is currently serving is ambiguous: a second
+/// write could follow within the same second and share the timestamp. Like
+/// the ambiguous-second guard in dirstate-style status checks, fold `len`
+/// into the hash in that case too, so same-second writes of different
+/// lengths still produce distinct tags.
+fn compute_etag(id: &str, updated_at: &str, len: u64) -> String {
+    let mut data = format!("{}:{}", id, updated_at);
+    if updated_at == current_timestamp() {
+        data.push(':');
+        data.push_str(&len.to_string());
+    }
+    format!("\"{:016x}\"", fnv1a(data.as_bytes()))
 }
 
 /// Directory entry for listings
@@ -809,6 +1802,15 @@ impl DavFileSystem for SqliteFs {
                         Ok(Box::new(SqliteDavFile::new(note, fs)) as Box<dyn DavFile>)
                     }
                 }
+                Ok(ResolvedPath::Feed) => {
+                    if options.write || options.append || options.create_new {
+                        eprintln!("[OPEN] Feed is read-only");
+                        return Err(FsError::Forbidden);
+                    }
+                    eprintln!("[OPEN] Rendering virtual Atom feed");
+                    let bytes = fs.render_feed()?;
+                    Ok(Box::new(FeedFile::new(bytes)) as Box<dyn DavFile>)
+                }
                 Ok(_) => {
                     eprintln!("[OPEN] Path resolved to folder or root - forbidden");
                     Err(FsError::Forbidden)
@@ -872,6 +1874,9 @@ impl DavFileSystem for SqliteFs {
                         syntax,
                         created_at: current_timestamp(),
                         updated_at: current_timestamp(),
+                        is_binary: false,
+                        content_blob: None,
+                        mime_type: None,
                     };
 
                     Ok(Box::new(SqliteDavFile::new_writable(note, fs, parent_id, true)) as Box<dyn DavFile>)
@@ -897,10 +1902,37 @@ impl DavFileSystem for SqliteFs {
                 ResolvedPath::Root => None,
                 ResolvedPath::Folder { id } => Some(id),
                 ResolvedPath::Note { .. } => return Err(FsError::Forbidden),
+                ResolvedPath::Feed => return Err(FsError::Forbidden),
+                ResolvedPath::RelationshipFolder { note_id, kind } => {
+                    let conn = fs.open_db()?;
+                    let related = fs.list_related(&conn, &note_id, &kind)?;
+                    let dir_entries: Vec<Box<dyn DavDirEntry>> = related
+                        .into_iter()
+                        .filter_map(|r| {
+                            let note = fs
+                                .get_note(r.parent_id.as_deref(), &r.title, &r.syntax)
+                                .ok()?;
+                            let len = note.len();
+                            let meta = SqliteMetaData {
+                                is_dir: false,
+                                len,
+                                modified: parse_datetime(&note.updated_at),
+                                created: parse_datetime(&note.created_at),
+                                etag: Some(compute_etag(&note.id, &note.updated_at, len)),
+                            };
+                            Some(Box::new(SqliteDirEntry {
+                                name: format!("{}.{}", r.title, r.syntax),
+                                metadata: meta,
+                            }) as Box<dyn DavDirEntry>)
+                        })
+                        .collect();
+                    return Ok(Box::pin(stream::iter(dir_entries.into_iter().map(Ok)))
+                        as FsStream<Box<dyn DavDirEntry>>);
+                }
             };
 
             let entries = fs.list_entries(parent_id.as_deref())?;
-            let dir_entries: Vec<Box<dyn DavDirEntry>> = entries
+            let mut dir_entries: Vec<Box<dyn DavDirEntry>> = entries
                 .into_iter()
                 .map(|e| {
                     let (name, metadata) = match e {
@@ -910,16 +1942,19 @@ impl DavFileSystem for SqliteFs {
                                 len: 0,
                                 modified: parse_datetime(&f.updated_at),
                                 created: parse_datetime(&f.created_at),
+                                etag: Some(compute_etag(&f.id, &f.updated_at, 0)),
                             };
                             (f.title, meta)
                         }
                         DirEntry::Note(n) => {
                             let filename = format!("{}.{}", n.title, n.syntax);
+                            let len = n.len();
                             let meta = SqliteMetaData {
                                 is_dir: false,
-                                len: n.content.len() as u64,
+                                len,
                                 modified: parse_datetime(&n.updated_at),
                                 created: parse_datetime(&n.created_at),
+                                etag: Some(compute_etag(&n.id, &n.updated_at, len)),
                             };
                             (filename, meta)
                         }
@@ -928,6 +1963,22 @@ impl DavFileSystem for SqliteFs {
                 })
                 .collect();
 
+            // The virtual feed only lives at the root, alongside real folders
+            // and notes.
+            if parent_id.is_none() {
+                let feed_len = fs.render_feed()?.len() as u64;
+                dir_entries.push(Box::new(SqliteDirEntry {
+                    name: FEED_NAME.to_string(),
+                    metadata: SqliteMetaData {
+                        is_dir: false,
+                        len: feed_len,
+                        modified: SystemTime::now(),
+                        created: SystemTime::now(),
+                        etag: None,
+                    },
+                }));
+            }
+
             Ok(Box::pin(stream::iter(dir_entries.into_iter().map(Ok)))
                 as FsStream<Box<dyn DavDirEntry>>)
         })
@@ -945,6 +1996,7 @@ impl DavFileSystem for SqliteFs {
                         len: 0,
                         modified: SystemTime::now(),
                         created: SystemTime::now(),
+                        etag: None,
                     };
                     Ok(Box::new(meta) as Box<dyn DavMetaData>)
                 }
@@ -955,6 +2007,7 @@ impl DavFileSystem for SqliteFs {
                         len: 0,
                         modified: parse_datetime(&folder.updated_at),
                         created: parse_datetime(&folder.created_at),
+                        etag: Some(compute_etag(&folder.id, &folder.updated_at, 0)),
                     };
                     Ok(Box::new(meta) as Box<dyn DavMetaData>)
                 }
@@ -964,11 +2017,35 @@ impl DavFileSystem for SqliteFs {
                     syntax,
                 } => {
                     let note = fs.get_note(parent_id.as_deref(), &title, &syntax)?;
+                    let len = note.len();
                     let meta = SqliteMetaData {
                         is_dir: false,
-                        len: note.content.len() as u64,
+                        len,
                         modified: parse_datetime(&note.updated_at),
                         created: parse_datetime(&note.created_at),
+                        etag: Some(compute_etag(&note.id, &note.updated_at, len)),
+                    };
+                    Ok(Box::new(meta) as Box<dyn DavMetaData>)
+                }
+                ResolvedPath::RelationshipFolder { .. } => {
+                    // A relationship folder is a synthetic directory view.
+                    let meta = SqliteMetaData {
+                        is_dir: true,
+                        len: 0,
+                        modified: SystemTime::now(),
+                        created: SystemTime::now(),
+                        etag: None,
+                    };
+                    Ok(Box::new(meta) as Box<dyn DavMetaData>)
+                }
+                ResolvedPath::Feed => {
+                    let len = fs.render_feed()?.len() as u64;
+                    let meta = SqliteMetaData {
+                        is_dir: false,
+                        len,
+                        modified: SystemTime::now(),
+                        created: SystemTime::now(),
+                        etag: None,
                     };
                     Ok(Box::new(meta) as Box<dyn DavMetaData>)
                 }
@@ -1003,13 +2080,89 @@ impl DavFileSystem for SqliteFs {
         Box::pin(async move {
             eprintln!("[GET_PROPS] path={}", path.as_url_string());
 
-            // Return empty properties for now
-            // This is enough to signal DAV compliance
-            match fs.resolve_path(&path) {
-                Ok(_) => Ok(vec![]),
-                Err(FsError::NotFound) => Ok(vec![]), // Even for non-existent files
-                Err(e) => Err(e),
+            // Root (and anything that fails to resolve) carries no properties.
+            let entity = match fs.entity_for_path(&path) {
+                Ok(Some(entity)) => entity,
+                Ok(None) => return Ok(vec![]),
+                Err(FsError::NotFound) => return Ok(vec![]),
+                Err(e) => return Err(e),
+            };
+
+            // Synthesized live properties plus any persisted dead properties.
+            let mut props = entity.live_props();
+            let conn = fs.open_db()?;
+            props.extend(fs.load_dead_props(&conn, entity.id())?);
+            Ok(props)
+        })
+    }
+
+    fn get_prop<'a>(&'a self, path: &'a DavPath, prop: DavProp) -> FsFuture<'a, Vec<u8>> {
+        let path = path.clone();
+        let fs = self.clone();
+
+        Box::pin(async move {
+            let entity = fs.entity_for_path(&path)?.ok_or(FsError::NotFound)?;
+
+            // A live property is answered from the entity's own columns.
+            let ns = prop.namespace.as_deref();
+            if ns == Some(LIVE_NS) || ns == Some(DAV_NS) {
+                if let Some(found) = entity
+                    .live_props()
+                    .into_iter()
+                    .find(|p| p.namespace.as_deref() == ns && p.name == prop.name)
+                {
+                    return found.xml.ok_or(FsError::NotFound);
+                }
             }
+
+            // Otherwise look up the stored dead property.
+            let conn = fs.open_db()?;
+            let namespace = prop.namespace.clone().unwrap_or_default();
+            conn.query_row(
+                "SELECT value FROM props WHERE entity_id = ? AND namespace = ? AND name = ?",
+                params![entity.id(), namespace, prop.name],
+                |row| row.get::<_, Option<Vec<u8>>>(0),
+            )
+            .map_err(|_| FsError::NotFound)?
+            .ok_or(FsError::NotFound)
+        })
+    }
+
+    fn patch_props<'a>(
+        &'a self,
+        path: &'a DavPath,
+        patch: Vec<(bool, DavProp)>,
+    ) -> FsFuture<'a, Vec<(StatusCode, DavProp)>> {
+        let path = path.clone();
+        let fs = self.clone();
+
+        Box::pin(async move {
+            let entity = fs.entity_for_path(&path)?.ok_or(FsError::Forbidden)?;
+            let id = entity.id().to_string();
+
+            // Apply all add/remove operations atomically.
+            fs.with_tx(|tx| {
+                let mut results = Vec::with_capacity(patch.len());
+                for (set, prop) in patch {
+                    let namespace = prop.namespace.clone().unwrap_or_default();
+                    if set {
+                        tx.execute(
+                            "INSERT INTO props (entity_id, namespace, name, value) VALUES (?, ?, ?, ?)
+                             ON CONFLICT(entity_id, namespace, name) DO UPDATE SET value = excluded.value",
+                            params![id, namespace, prop.name, prop.xml],
+                        )
+                        .map_err(|_| FsError::GeneralFailure)?;
+                    } else {
+                        tx.execute(
+                            "DELETE FROM props WHERE entity_id = ? AND namespace = ? AND name = ?",
+                            params![id, namespace, prop.name],
+                        )
+                        .map_err(|_| FsError::GeneralFailure)?;
+                    }
+                    results.push((StatusCode::OK, prop));
+                }
+                Ok(results)
+            })
         })
     }
 
@@ -1087,6 +2240,14 @@ impl DavFileSystem for SqliteFs {
                     eprintln!("[REMOVE_FILE] Cannot delete root");
                     Err(FsError::Forbidden)
                 }
+                ResolvedPath::RelationshipFolder { .. } => {
+                    eprintln!("[REMOVE_FILE] Cannot delete a synthetic relationship folder");
+                    Err(FsError::Forbidden)
+                }
+                ResolvedPath::Feed => {
+                    eprintln!("[REMOVE_FILE] Cannot delete the virtual feed");
+                    Err(FsError::Forbidden)
+                }
             }
         })
     }
@@ -1116,6 +2277,14 @@ impl DavFileSystem for SqliteFs {
                     eprintln!("[REMOVE_DIR] Cannot delete root directory");
                     Err(FsError::Forbidden)
                 }
+                ResolvedPath::RelationshipFolder { .. } => {
+                    eprintln!("[REMOVE_DIR] Cannot delete a synthetic relationship folder");
+                    Err(FsError::Forbidden)
+                }
+                ResolvedPath::Feed => {
+                    eprintln!("[REMOVE_DIR] Cannot delete the virtual feed");
+                    Err(FsError::Forbidden)
+                }
             }
         })
     }
@@ -1204,22 +2373,165 @@ impl DavFileSystem for SqliteFs {
                     eprintln!("[RENAME] Cannot rename root");
                     return Err(FsError::Forbidden);
                 }
+                ResolvedPath::RelationshipFolder { .. } => {
+                    eprintln!("[RENAME] Cannot rename a synthetic relationship folder");
+                    return Err(FsError::Forbidden);
+                }
+                ResolvedPath::Feed => {
+                    eprintln!("[RENAME] Cannot rename the virtual feed");
+                    return Err(FsError::Forbidden);
+                }
             }
 
             eprintln!("[RENAME] Rename completed successfully");
             Ok(())
         })
     }
+
+    fn copy<'a>(&'a self, from: &'a DavPath, to: &'a DavPath) -> FsFuture<'a, ()> {
+        let from = from.clone();
+        let to = to.clone();
+        let fs = self.clone();
+
+        Box::pin(async move {
+            eprintln!("[COPY] from={} to={}", from.as_url_string(), to.as_url_string());
+
+            // Parse the destination path first (common to both notes and folders)
+            let to_str = to.as_url_string();
+            let to_str = to_str.trim_start_matches('/').trim_end_matches('/');
+
+            if to_str.is_empty() {
+                eprintln!("[COPY] Destination path is empty");
+                return Err(FsError::Forbidden);
+            }
+
+            // URL-decode path components
+            let components: Vec<String> = to_str
+                .split('/')
+                .map(|c| percent_decode_str(c).decode_utf8_lossy().to_string())
+                .collect();
+
+            let dst_name = components.last().ok_or(FsError::Forbidden)?.clone();
+
+            // Resolve destination parent folder
+            let conn = fs.open_db()?;
+            let mut dst_parent_id: Option<String> = None;
+
+            for component in components.iter().take(components.len() - 1) {
+                dst_parent_id = fs.find_folder(&conn, dst_parent_id.as_deref(), component)?;
+                if dst_parent_id.is_none() {
+                    eprintln!("[COPY] Destination parent folder '{}' not found", component);
+                    return Err(FsError::NotFound);
+                }
+            }
+            drop(conn);
+
+            // Resolve the source path and handle based on type. Both branches
+            // copy server-side in one transaction: rows are read from SQLite and
+            // the copies written back, so note content never streams through
+            // the client the way a generic read/write copy would.
+            match fs.resolve_path(&from)? {
+                ResolvedPath::Note {
+                    parent_id: src_parent_id,
+                    title: src_title,
+                    syntax: src_syntax,
+                } => {
+                    eprintln!(
+                        "[COPY] Source note: parent_id={:?}, title={}, syntax={}",
+                        src_parent_id, src_title, src_syntax
+                    );
+
+                    let (dst_title, dst_syntax) = parse_filename(&dst_name).ok_or_else(|| {
+                        eprintln!("[COPY] Invalid destination filename (no extension): {}", dst_name);
+                        FsError::Forbidden
+                    })?;
+
+                    fs.copy_note(
+                        src_parent_id.as_deref(),
+                        &src_title,
+                        &src_syntax,
+                        dst_parent_id.as_deref(),
+                        &dst_title,
+                        &dst_syntax,
+                    )?;
+                }
+                ResolvedPath::Folder { id: folder_id } => {
+                    eprintln!("[COPY] Source folder: id={}", folder_id);
+                    fs.copy_folder(&folder_id, dst_parent_id.as_deref(), &dst_name)?;
+                }
+                ResolvedPath::Root => {
+                    eprintln!("[COPY] Cannot copy root");
+                    return Err(FsError::Forbidden);
+                }
+                ResolvedPath::RelationshipFolder { .. } => {
+                    eprintln!("[COPY] Cannot copy a synthetic relationship folder");
+                    return Err(FsError::Forbidden);
+                }
+                ResolvedPath::Feed => {
+                    eprintln!("[COPY] Cannot copy the virtual feed");
+                    return Err(FsError::Forbidden);
+                }
+            }
+
+            eprintln!("[COPY] Copy completed successfully");
+            Ok(())
+        })
+    }
+}
+
+/// Parse a numeric UTC offset (`+05:00`, `-08:00`, or the bare `+0530` form)
+/// into a signed second count, positive east of UTC.
+fn parse_offset(s: &str) -> Option<i64> {
+    let sign = match s.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let rest = &s[1..];
+    let (hours, mins) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => return None,
+    };
+    let hours: i64 = hours.parse().ok()?;
+    let mins: i64 = mins.parse().ok()?;
+    Some(sign * (hours * 3600 + mins * 60))
 }
 
-/// Parse SQLite datetime string to SystemTime
-fn parse_datetime(s: &str) -> SystemTime {
-    // SQLite stores as "YYYY-MM-DD HH:MM:SS"
-    // For simplicity, return current time on parse failure
+/// Parse a SQLite timestamp into a `SystemTime`.
+///
+/// Every write in this module stores [`current_timestamp`]'s
+/// `YYYY-MM-DD HH:MM:SS` form in UTC, but accept the RFC3339 `T`
+/// separator, fractional seconds, a trailing `Z`, and a numeric `+HH:MM`/
+/// `-HH:MM` offset too, since rows written by another tool may use that
+/// shape. Falls back to the current time only when the string truly doesn't
+/// parse.
+pub(super) fn parse_datetime(s: &str) -> SystemTime {
     use std::time::Duration;
 
-    // Simple parsing - in production use chrono
-    let parts: Vec<&str> = s.split(&['-', ' ', ':'][..]).collect();
+    let trimmed = s.trim();
+    let (body, offset_secs) = match trimmed.strip_suffix('Z') {
+        Some(rest) => (rest, 0),
+        None => {
+            // A numeric offset can only appear after the time-of-day, so look
+            // for its sign there rather than in the whole string, where it
+            // would collide with the date's own '-' separators.
+            let time_start = trimmed.find(&['T', ' '][..]).map_or(0, |i| i + 1);
+            match trimmed[time_start..].find(&['+', '-'][..]) {
+                Some(i) => {
+                    let split_at = time_start + i;
+                    let offset = parse_offset(&trimmed[split_at..]).unwrap_or(0);
+                    (&trimmed[..split_at], offset)
+                }
+                None => (trimmed, 0),
+            }
+        }
+    };
+
+    let without_frac = body.split_once('.').map_or(body, |(head, _)| head);
+    let normalized = without_frac.replacen('T', " ", 1);
+
+    let parts: Vec<&str> = normalized.split(&['-', ' ', ':'][..]).collect();
     if parts.len() >= 6 {
         if let (Ok(year), Ok(month), Ok(day), Ok(hour), Ok(min), Ok(sec)) = (
             parts[0].parse::<i64>(),
@@ -1229,11 +2541,79 @@ fn parse_datetime(s: &str) -> SystemTime {
             parts[4].parse::<i64>(),
             parts[5].parse::<i64>(),
         ) {
-            // Approximate: days since epoch
-            let days_since_epoch = (year - 1970) * 365 + (month - 1) * 30 + day;
-            let secs = (days_since_epoch * 86400 + hour * 3600 + min * 60 + sec) as u64;
-            return SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            let days = days_from_civil(year, month, day);
+            // Offset is local time minus UTC, so subtract it to land on UTC.
+            let secs = days * 86400 + hour * 3600 + min * 60 + sec - offset_secs;
+            if secs >= 0 {
+                return SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64);
+            }
+            return SystemTime::UNIX_EPOCH - Duration::from_secs((-secs) as u64);
         }
     }
     SystemTime::now()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn civil_days_round_trip() {
+        // A handful of dates, including leap-year boundaries, survive a
+        // date -> days -> date round trip unchanged.
+        for &(y, m, d) in &[
+            (1970, 1, 1),
+            (2000, 2, 29),
+            (2021, 12, 31),
+            (2024, 2, 29),
+            (1999, 7, 4),
+        ] {
+            let days = days_from_civil(y, m, d);
+            assert_eq!(civil_from_days(days), (y, m, d));
+        }
+    }
+
+    #[test]
+    fn epoch_is_day_zero() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+    }
+
+    #[test]
+    fn parse_datetime_is_exact() {
+        // 2021-01-01 00:00:00 UTC is exactly 1609459200 seconds after the epoch.
+        let t = parse_datetime("2021-01-01 00:00:00");
+        let secs = t.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+        assert_eq!(secs, Duration::from_secs(1_609_459_200));
+    }
+
+    #[test]
+    fn parse_datetime_accepts_rfc3339_variant() {
+        // Same instant as above, but with a 'T' separator, fractional
+        // seconds, and a trailing 'Z'.
+        let t = parse_datetime("2021-01-01T00:00:00.123Z");
+        let secs = t.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+        assert_eq!(secs, Duration::from_secs(1_609_459_200));
+    }
+
+    #[test]
+    fn parse_datetime_accepts_numeric_offset() {
+        // Same instant as above, expressed in two different non-UTC offsets.
+        let east = parse_datetime("2021-01-01T05:30:00+05:30");
+        let west = parse_datetime("2020-12-31T16:00:00-08:00");
+        let secs = Duration::from_secs(1_609_459_200);
+        assert_eq!(east.duration_since(SystemTime::UNIX_EPOCH).unwrap(), secs);
+        assert_eq!(west.duration_since(SystemTime::UNIX_EPOCH).unwrap(), secs);
+    }
+
+    #[test]
+    fn guess_mime_type_known_extensions() {
+        assert_eq!(guess_mime_type("png"), "image/png");
+        assert_eq!(guess_mime_type("PDF"), "application/pdf");
+    }
+
+    #[test]
+    fn guess_mime_type_unknown_extension_falls_back() {
+        assert_eq!(guess_mime_type("md"), "application/octet-stream");
+    }
+}
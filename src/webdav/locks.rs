@@ -0,0 +1,294 @@
+//! A WebDAV class-2 lock system backed by SQLite.
+//!
+//! Locks are persisted in the `locks` table (see the `webdav_locks` migration)
+//! so that an exclusive write lock taken by one client survives a server
+//! restart and is honoured by every worker sharing the database. Each lock is
+//! an `opaquelocktoken:` UUID with a path, depth, scope (exclusive/shared),
+//! owner XML and an absolute expiry; stale locks are pruned lazily whenever the
+//! table is consulted.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dav_server::davpath::DavPath;
+use dav_server::ls::{DavLock, DavLockSystem};
+use rusqlite::params;
+use uuid::Uuid;
+use xmltree::Element;
+
+use super::filesystem::DbPool;
+
+/// Default lock lifetime when a client does not request a timeout.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A SQLite-backed implementation of [`DavLockSystem`].
+#[derive(Debug, Clone)]
+pub struct SqliteLs {
+    pool: DbPool,
+}
+
+impl SqliteLs {
+    /// Build a lock system sharing the filesystem's connection pool.
+    pub fn new(pool: DbPool) -> Box<SqliteLs> {
+        Box::new(SqliteLs { pool })
+    }
+
+    /// Current wall-clock time in whole seconds since the Unix epoch.
+    fn now_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
+    /// Drop every lock whose expiry has passed.
+    fn reap(&self) {
+        if let Ok(conn) = self.pool.get() {
+            let _ = conn.execute(
+                "DELETE FROM locks WHERE expires_at IS NOT NULL AND expires_at <= ?",
+                params![Self::now_secs() as i64],
+            );
+        }
+    }
+
+    /// Load all live locks, in no particular order, as in-memory [`DavLock`]s.
+    fn all_locks(&self) -> Vec<DavLock> {
+        let conn = match self.pool.get() {
+            Ok(c) => c,
+            Err(_) => return Vec::new(),
+        };
+        let mut stmt = match conn.prepare(
+            "SELECT token, path, principal, owner, shared, deep, timeout_secs, expires_at FROM locks",
+        ) {
+            Ok(s) => s,
+            Err(_) => return Vec::new(),
+        };
+        let now = Self::now_secs();
+        let rows = stmt.query_map([], |row| {
+            let token: String = row.get(0)?;
+            let path: String = row.get(1)?;
+            let principal: Option<String> = row.get(2)?;
+            let owner: Option<String> = row.get(3)?;
+            let shared: i64 = row.get(4)?;
+            let deep: i64 = row.get(5)?;
+            let timeout_secs: Option<i64> = row.get(6)?;
+            let expires_at: Option<i64> = row.get(7)?;
+            Ok(LockRow {
+                token,
+                path,
+                principal,
+                owner,
+                shared: shared != 0,
+                deep: deep != 0,
+                timeout_secs,
+                expires_at,
+            })
+        });
+        let rows = match rows {
+            Ok(r) => r,
+            Err(_) => return Vec::new(),
+        };
+        rows.filter_map(Result::ok)
+            .filter(|r| r.expires_at.map(|e| e as u64 > now).unwrap_or(true))
+            .filter_map(|r| r.into_dav_lock())
+            .collect()
+    }
+
+    /// Insert (or replace, by token) the row for `lock`. Database errors are
+    /// ignored.
+    fn insert(&self, lock: &DavLock, timeout_secs: Option<i64>, expires_at: Option<i64>) {
+        if let Ok(conn) = self.pool.get() {
+            let _ = conn.execute(
+                "INSERT OR REPLACE INTO locks
+                     (token, path, principal, owner, shared, deep, timeout_secs, expires_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    lock.token,
+                    lock.path.as_url_string(),
+                    lock.principal,
+                    lock.owner.as_ref().map(serialize_owner),
+                    lock.shared as i64,
+                    lock.deep as i64,
+                    timeout_secs,
+                    expires_at,
+                ],
+            );
+        }
+    }
+}
+
+/// A raw row from the `locks` table, before conversion to [`DavLock`].
+struct LockRow {
+    token: String,
+    path: String,
+    principal: Option<String>,
+    owner: Option<String>,
+    shared: bool,
+    deep: bool,
+    timeout_secs: Option<i64>,
+    expires_at: Option<i64>,
+}
+
+impl LockRow {
+    fn into_dav_lock(self) -> Option<DavLock> {
+        let path = DavPath::new(&self.path).ok()?;
+        let timeout_at = self.expires_at.map(|e| {
+            let remaining = (e as u64).saturating_sub(SqliteLs::now_secs());
+            Instant::now() + Duration::from_secs(remaining)
+        });
+        Some(DavLock {
+            token: self.token,
+            path,
+            principal: self.principal,
+            owner: self.owner.as_deref().and_then(parse_owner),
+            timeout_at,
+            timeout: self.timeout_secs.map(|s| Duration::from_secs(s as u64)),
+            shared: self.shared,
+            deep: self.deep,
+        })
+    }
+}
+
+/// True if a lock held at `lock` covers an operation on `target` given the
+/// operation's own depth: a lock covers itself, a deep lock covers its whole
+/// subtree, and a deep operation is constrained by any lock inside its subtree.
+fn covers(lock: &DavLock, target: &DavPath, target_deep: bool) -> bool {
+    let lp = lock.path.as_url_string();
+    let tp = target.as_url_string();
+    if lp == tp {
+        return true;
+    }
+    if lock.deep && is_prefix(&lp, &tp) {
+        return true;
+    }
+    if target_deep && is_prefix(&tp, &lp) {
+        return true;
+    }
+    false
+}
+
+/// True if `ancestor` names a proper path-prefix of `descendant`.
+fn is_prefix(ancestor: &str, descendant: &str) -> bool {
+    let a = ancestor.trim_end_matches('/');
+    descendant.starts_with(a) && descendant.as_bytes().get(a.len()) == Some(&b'/')
+}
+
+fn serialize_owner(owner: &Element) -> String {
+    let mut buf = Vec::new();
+    let _ = owner.write(&mut buf);
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+fn parse_owner(s: &str) -> Option<Element> {
+    Element::parse(s.as_bytes()).ok()
+}
+
+impl DavLockSystem for SqliteLs {
+    fn lock(
+        &self,
+        path: &DavPath,
+        principal: Option<&str>,
+        owner: Option<&Element>,
+        timeout: Option<Duration>,
+        shared: bool,
+        deep: bool,
+    ) -> Result<DavLock, ()> {
+        self.reap();
+
+        // Reject if a conflicting lock already covers this path.
+        for existing in self.all_locks() {
+            if covers(&existing, path, deep) && (!existing.shared || !shared) {
+                return Err(());
+            }
+        }
+
+        let timeout = timeout.or(Some(DEFAULT_TIMEOUT));
+        let timeout_secs = timeout.map(|d| d.as_secs() as i64);
+        let expires_at = timeout_secs.map(|s| Self::now_secs() as i64 + s);
+
+        let lock = DavLock {
+            token: format!("opaquelocktoken:{}", Uuid::new_v4()),
+            path: path.clone(),
+            principal: principal.map(str::to_string),
+            owner: owner.cloned(),
+            timeout_at: timeout.map(|d| Instant::now() + d),
+            timeout,
+            shared,
+            deep,
+        };
+        self.insert(&lock, timeout_secs, expires_at);
+        Ok(lock)
+    }
+
+    fn unlock(&self, _path: &DavPath, token: &str) -> Result<(), ()> {
+        let conn = self.pool.get().map_err(|_| ())?;
+        let n = conn
+            .execute("DELETE FROM locks WHERE token = ?", params![token])
+            .map_err(|_| ())?;
+        if n == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn refresh(
+        &self,
+        _path: &DavPath,
+        token: &str,
+        timeout: Option<Duration>,
+    ) -> Result<DavLock, ()> {
+        self.reap();
+
+        let mut lock = self
+            .all_locks()
+            .into_iter()
+            .find(|l| l.token == token)
+            .ok_or(())?;
+
+        let timeout = timeout.or(lock.timeout).or(Some(DEFAULT_TIMEOUT));
+        let timeout_secs = timeout.map(|d| d.as_secs() as i64);
+        let expires_at = timeout_secs.map(|s| Self::now_secs() as i64 + s);
+        lock.timeout = timeout;
+        lock.timeout_at = timeout.map(|d| Instant::now() + d);
+
+        self.insert(&lock, timeout_secs, expires_at);
+        Ok(lock)
+    }
+
+    fn check(
+        &self,
+        path: &DavPath,
+        _principal: Option<&str>,
+        _ignore_principal: bool,
+        deep: bool,
+        submitted_tokens: Vec<&str>,
+    ) -> Result<(), DavLock> {
+        self.reap();
+
+        for existing in self.all_locks() {
+            if covers(&existing, path, deep) && !submitted_tokens.contains(&existing.token.as_str())
+            {
+                return Err(existing);
+            }
+        }
+        Ok(())
+    }
+
+    fn discover(&self, path: &DavPath) -> Vec<DavLock> {
+        self.reap();
+        self.all_locks()
+            .into_iter()
+            .filter(|l| covers(l, path, true))
+            .collect()
+    }
+
+    fn delete(&self, path: &DavPath) -> Result<(), ()> {
+        let conn = self.pool.get().map_err(|_| ())?;
+        conn.execute(
+            "DELETE FROM locks WHERE path = ?",
+            params![path.as_url_string()],
+        )
+        .map_err(|_| ())?;
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 pub mod auth;
 pub mod davfile;
 pub mod filesystem;
+pub mod locks;
+pub mod users;
 
-pub use auth::extract_basic_auth;
-pub use filesystem::SqliteFs;
+pub use auth::{extract_basic_auth, extract_bearer};
+pub use filesystem::{build_pool, DbPool, SqliteFs};
+pub use locks::SqliteLs;
+pub use users::{AuthProvider, SingleUserProvider, TableAuthProvider};
@@ -0,0 +1,94 @@
+//! Pluggable authentication providers backing Basic Auth verification.
+//!
+//! The server can run in a simple single-user mode (one username/password
+//! pair from the CLI or config file) or look users up from the `users`
+//! table, so a single server instance can serve many notebooks/users keyed
+//! by their own `user_id`. Both modes implement [`AuthProvider`], so
+//! `handle_request` doesn't need to know which one is active. Stored
+//! passwords are Argon2id PHC strings (see [`crate::password`]), verified
+//! rather than compared.
+
+use rusqlite::params;
+use subtle::ConstantTimeEq;
+
+use super::filesystem::DbPool;
+
+/// A user that successfully authenticated, and the `user_id` to scope their
+/// notes/folders to.
+pub struct AuthenticatedUser {
+    pub user_id: String,
+}
+
+/// Verifies Basic Auth credentials and maps them to a `user_id`.
+pub trait AuthProvider {
+    fn authenticate(&self, username: &str, password: &str) -> Option<AuthenticatedUser>;
+}
+
+/// Fallback provider: a single fixed username and Argon2id password hash
+/// from the CLI or config file, mapped to one fixed `user_id`.
+pub struct SingleUserProvider {
+    pub username: String,
+    pub password_hash: String,
+    pub user_id: String,
+}
+
+impl AuthProvider for SingleUserProvider {
+    fn authenticate(&self, username: &str, password: &str) -> Option<AuthenticatedUser> {
+        // Compare the username in constant time to avoid leaking a match via
+        // response timing; Argon2's own verification is already constant-time.
+        let username_match: bool = username.as_bytes().ct_eq(self.username.as_bytes()).into();
+        let password_match = crate::password::verify(password, &self.password_hash);
+
+        if username_match && password_match {
+            Some(AuthenticatedUser {
+                user_id: self.user_id.clone(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Looks users up in the `users` table (username, password, user_id),
+/// falling back to `fallback` when no row matches so the single-user CLI
+/// mode keeps working untouched on databases that have never added a user.
+pub struct TableAuthProvider {
+    pool: DbPool,
+    fallback: SingleUserProvider,
+}
+
+impl TableAuthProvider {
+    pub fn new(pool: DbPool, fallback: SingleUserProvider) -> Self {
+        Self { pool, fallback }
+    }
+
+    fn lookup(&self, username: &str) -> Option<UserRow> {
+        let conn = self.pool.get().ok()?;
+        conn.query_row(
+            "SELECT password, user_id FROM users WHERE username = ?",
+            params![username],
+            |row| {
+                Ok(UserRow {
+                    password_hash: row.get(0)?,
+                    user_id: row.get(1)?,
+                })
+            },
+        )
+        .ok()
+    }
+}
+
+impl AuthProvider for TableAuthProvider {
+    fn authenticate(&self, username: &str, password: &str) -> Option<AuthenticatedUser> {
+        match self.lookup(username) {
+            Some(row) => crate::password::verify(password, &row.password_hash)
+                .then_some(AuthenticatedUser { user_id: row.user_id }),
+            None => self.fallback.authenticate(username, password),
+        }
+    }
+}
+
+struct UserRow {
+    password_hash: String,
+    user_id: String,
+}